@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy_toolbox::*;
+use bevy_toolbox::prelude::*;
 
 
 fn main() {
@@ -54,7 +54,7 @@ fn setup(mut commands: Commands) {
 fn create_stylish_button(mut entity: EntityCommands, index: usize) {
   let base = entity.id();
 
-  spawn! { [entity.commands()]
+  spawn! { @
     base + (
       Button,
       BorderRadius::all(v!(5px)),
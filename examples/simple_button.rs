@@ -1,5 +1,8 @@
+// the expansions have to stay clean in crates with strict lint setups
+#![deny(warnings)]
+
 use bevy::prelude::*;
-use bevy_toolbox::*;
+use bevy_toolbox::prelude::*;
 
 
 fn main() {
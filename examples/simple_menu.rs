@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy_toolbox::*;
+use bevy_toolbox::prelude::*;
 use bevy::{ecs::system::IntoObserverSystem, window::PrimaryWindow, winit::cursor::CursorIcon};
 
 
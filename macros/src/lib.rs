@@ -0,0 +1,1647 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::*;
+use syn::parse::*;
+use bevy_toolbox_parser::Generate;
+
+
+/// This macro is used to simplify the entity creation of the bevy engine.
+///
+/// # Basics
+///
+/// ## Spawner
+///
+/// Spawner is the object that spawns the entities, it has to implement the `Spawnable` trait, which is
+/// implemented for `Commands` and `World`. Using anything else will be reported as a missing `Spawnable`
+/// implementation. This includes the child spawners of `with_children` and `with_related`, use
+/// `[parent.commands()]` to spawn with them instead.
+///
+/// Every form of the macro works with both spawners, except the following ones, which need the
+/// `Commands` of the entity and therefore require the spawner to be `Commands`:
+///
+/// | Form                        | Requires   |
+/// |-----------------------------|------------|
+/// | `.observe_global(...)`      | `Commands` |
+/// | `.name = (...)`             | `Commands` |
+/// | `.children_at(index)[...]`  | `Commands` |
+/// | `@` as the spawner          | `Commands` |
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands) {
+///   // commands can be used as spawner
+/// }
+///
+/// fn bar(mut world: &mut World) {
+///   // world can be used as spawner, the binding has to be `mut` since it's borrowed mutably
+/// }
+///
+/// fn baz(world: &mut World) {
+///   // or the reference is passed as is
+///   spawn! { [&mut *world] (Camera2d); }
+/// }
+/// ```
+///
+/// A field of a binding, like `self.commands` or `ctx.world`, can be used without `[]` as well, it's
+/// borrowed the same as an identifier.
+///
+/// ```rs
+/// fn foo(ctx: &mut Context) {
+///   spawn! { ctx.commands (Camera2d); }
+/// }
+/// ```
+///
+/// If you want to use the expression for the spawner, you can wrap it with `[]`. It's required for
+/// anything other than an identifier or a field, e.g. a method call.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands) {
+///   spawn! { [commands.reborrow()] }
+/// }
+/// ```
+///
+/// An expression spawner is moved into the macro. When it's a `Commands` taken by value, the
+/// `commands` binding can't be used after the macro anymore. Appending `reborrow` to the expression
+/// reborrows it instead, which is the same as writing `[commands.reborrow()]`.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands) {
+///   spawn! { [commands reborrow] (Camera2d); }
+///   spawn! { [commands reborrow] (Button); }
+/// }
+/// ```
+///
+/// An expression starting with `&mut` is already a reference to the spawner, so it's used as is rather
+/// than moved into a binding of its own. `[&mut commands]` borrows `commands` only for the duration of
+/// the macro, the same goes for `[&mut *world]` with `world: &mut World`.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands) {
+///   spawn! { [&mut commands] (Camera2d); }
+///   spawn! { [&mut commands] (Button); }
+/// }
+/// ```
+///
+/// Inside a code block extension, or any other place where an `EntityCommands` named `entity` is in
+/// scope, `@` can be used as the spawner. It is a shorthand for `[entity.commands()]`, so the nested
+/// macro spawns with the same `Commands` as the enclosing one. The spawned entities are not related
+/// to `entity` in any way, use `this > ...` or `entity.id()` to place them under it.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Node::default()).{
+///     spawn! { @ this > (Text::new("Nested")); }
+///   };
+/// }
+/// ```
+///
+/// With the `default-spawner` feature enabled, the spawner can be omitted when the macro starts with
+/// anything other than an identifier or `[`. An identifier named `commands` is then used as the
+/// spawner, so it must be in scope. Since `root (Node::default())` would read `root` as the spawner,
+/// the spawner has to be written if the first statement starts with a name or a bare component.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands) {
+///   spawn! {
+///     (Camera2d);
+///     (Button);
+///   }
+/// }
+/// ```
+///
+/// ## Top level
+///
+/// Top level means the part of the macro thats been directly quoted by the macro itself.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   // here is the top level
+///
+///   ().[
+///     // here is not the top level
+///   ];
+/// }
+/// ```
+///
+/// ## Entity definition
+///
+/// An entity definition is a tuple of components that will be spawned as an entity.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   // entity definition
+///   (Button, Node::default());
+/// }
+/// ```
+///
+/// Top level can accept multiple entity definitions.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   // entity 1
+///   (Button, Node::default());
+///
+///   // entity 2
+///   (Button, Node::default());
+/// }
+/// ```
+///
+/// A single component at the top level can be written without the parentheses, as long as nothing
+/// follows it but `;` or the end of the macro. It has to start with a path, like `Camera2d`,
+/// `Node::default()` or `Node { .. }`. To name it, extend it or give it children, the parentheses are
+/// still needed. A tuple struct constructor like `A(20)` needs them as well, since `A(20);` reads as an
+/// entity named `A`, it's rejected with an error instead.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   // same as `(Camera2d);`
+///   Camera2d;
+///   Node::default();
+/// 
+///   // `A(20);` is an error, the parentheses are needed
+///   (A(20));
+/// }
+/// ```
+///
+/// The components are passed to the spawner exactly like a Rust tuple is written, so a trailing comma
+/// is allowed. A single component without comma is spawned as the component itself, while a single
+/// component followed by a comma is spawned as a 1-tuple, both end up with the same entity.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button);                   // spawns `Button`
+///   (Button,);                  // spawns `(Button,)`
+///   (Button, Node::default(),); // trailing comma is fine
+///   ();                         // spawns an empty entity with `spawn_empty`
+/// }
+/// ```
+///
+/// Since a tuple of bundles is a bundle as well, an existing bundle can be listed next to other
+/// components with `(my_bundle, Extra)`. However, a bundle can't contain the same component twice, so
+/// when `Extra` is already part of `my_bundle`, prefix the bundle with `..` instead. The bundle is then
+/// spawned first and the rest of the components, along with the label if any, are inserted after it,
+/// replacing the ones the bundle brought.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, prefab: (Button, Node, BackgroundColor)) {
+///   spawn! { commands
+///     (..prefab.clone(), BackgroundColor(c!(#f00)));
+///   }
+/// }
+/// ```
+///
+/// Several bundles can be spread with `(..base, ..theme, Extra)`. The first one is spawned and the
+/// following ones are inserted in the order they are written, each replacing the components it shares
+/// with the bundles before it, and the rest of the components are inserted last.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, base: (Button, Node), theme: (Node, BackgroundColor)) {
+///   spawn! { commands
+///     (..base.clone(), ..theme.clone(), BorderRadius::MAX);
+///   }
+/// }
+/// ```
+///
+/// A component prefixed with `+` is left out of the spawned bundle and inserted on its own right
+/// after the entity is spawned. The insertions happen in the order they are written, after the bundle
+/// and after the components following `..bundle`, and before any extension. This is useful when a
+/// component requires another one and the required component has to be in place first, or should be
+/// inserted by the requiring component instead of being given explicitly.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   // spawns `(Node::default(),)`, then inserts `Button`, then `BackgroundColor`
+///   (Node::default(), +Button, +BackgroundColor(c!(#f00)));
+/// }
+/// ```
+///
+/// With the `component-shorthand` feature enabled, a few single field components can be written as
+/// `name: value` in the component list. Only the following shorthands are recognized, anything else
+/// written this way is an error.
+///
+/// | Shorthand       | Expands to               |
+/// |-----------------|--------------------------|
+/// | `bg: value`     | `BackgroundColor(value)` |
+/// | `border: value` | `BorderColor(value)`     |
+/// | `color: value`  | `TextColor(value)`       |
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Text::new("Hello"), bg: c!(#000), color: c!(#fff));
+/// }
+/// ```
+///
+/// ## Order
+///
+/// The order of any bit in the macro matters. The execution order is strictly follow the macro input.
+///
+/// ```rs, no_run
+/// // entity `a` will always being spawned before `b`
+/// spawn! { commands
+///   a ();
+///   b ();
+/// }
+/// ```
+///
+/// Within a single entity, the generated code runs in this order:
+///
+/// 1. The entity is spawned with its components, in one bundle.
+/// 1. The `..bundle` spread and `+component` insertions, if any.
+/// 1. The extensions before the first children group, in source order.
+/// 1. The children groups, in source order.
+/// 1. The extensions after the children groups, in source order.
+///
+/// Nothing is reordered past that, so a component that has to be inserted after an observer is added
+/// can be inserted with `.insert(...)` in between the extensions.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button)
+///     .(|_: Trigger<OnInsert, Pressed>| println!("Pressed!"))
+///     // inserted after the observer exists, so the observer sees it
+///     .insert(Pressed);
+/// }
+/// ```
+///
+/// ## Naming
+///
+/// An entity can be named for later reference. The variable will hold the `Entity` of the corresponding
+/// entity, NOT THE `EntityCommands`.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   entity_1 (Button, Node::default());
+///   entity_2 (Button, Node::default());
+///
+///   (Button)
+///     .(move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+///       // referencing the entity_1 after it's been spawned
+///       commands.entity(entity_1).despawn();
+///     });
+///
+///   {
+///     println!("{entity_1:?}");
+///     println!("{entity_2:?}");
+///   };
+/// }
+/// ```
+///
+/// ## Labeling
+///
+/// An entity can be given a `Name` component by putting `#` followed by a string literal right before
+/// the definition. The string is passed to `format!`, so any variable in scope can be interpolated,
+/// which is handy for telling apart the entities spawned in a loop.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   #"Container" (Node::default());
+///
+///   // labeling and naming can be combined
+///   button #"Play" (Button);
+///
+///   for i in 0..10 {
+///     // `Name::new(format!("Button {i}"))` is inserted
+///     #"Button {i}" (Button);
+///   }
+/// }
+/// ```
+///
+/// When the label would just repeat the name, `#` can be put right before the name instead. The entity is
+/// named as usual, and the name itself is inserted as its `Name`.
+///
+/// ```rs
+/// spawn! { commands
+///   // same as `health_bar #"health_bar" (Node::default())`
+///   #health_bar (Node::default());
+/// }
+/// ```
+///
+/// ## Parenting
+///
+/// A top level entities can have explicit parent. Parenting is done by using `>` operator. The flow
+/// bodies at the top level are top level as well, so the parent can be picked by a condition, while
+/// inside of a children group the parent is always the entity owning the group.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   my_entity (Button);
+///
+///   // this entity will be spawned as a child of `my_entity`
+///   my_entity > (Button);
+///
+///   // it's also possible to use the entity outside the macro
+///   // just make sure the parent is `Entity` type
+///   some_outside_entity > (Button);
+///
+///   // parenting and naming can be combined
+///   parent > child (Button);
+///
+///   // any expression evaluating to an `Entity` can be the parent when put in `[]`, it's only evaluated once
+///   [panels.single().unwrap()] > (Button);
+///
+///   // the parent can also be given after the entity with `<`, same as `parent > label (Text::new("x"))`
+///   label (Text::new("x")) < parent;
+///
+///   // an existing entity can be moved under a parent without spawning a new one
+///   my_entity > some_outside_entity;
+///
+///   // the parent can be chosen by a flow statement
+///   if some_condition { my_entity > (Button); } else { some_outside_entity > (Button); }
+/// }
+/// ```
+///
+/// Since `ChildOf` is a plain component, a top level entity can also get its parent by listing it
+/// with the other components. Writing `ChildOf` for an entity which already has a parent given by
+/// `>`, or by being in a children group, is rejected at compile time, as one would silently replace
+/// the other.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   my_entity (Button);
+///
+///   // same as `my_entity > (Button);`
+///   (Button, ChildOf(my_entity));
+///
+///   // error: `ChildOf` conflicts with the parent given by the macro
+///   my_entity > (Button, ChildOf(my_entity));
+/// }
+/// ```
+///
+/// ## Insertion
+///
+/// Insertion is a way to add some components to an existing entity. The entity must be named and spawned
+/// in advanced in order to be referenced.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   my_entity (Button);
+///
+///   // add a background color to `my_entity`
+///   my_entity + (BackgroundColor(Color::srgb(0.0, 0.0, 0.0)));
+///
+///   // in the children group, it's also possible to insert components
+///   my_fancy_button (Button).[
+///     txt (Text::new("Hello, World!"));
+///
+///     // add a background color to `txt`
+///     txt + (BackgroundColor(Color::srgb(0.0, 0.0, 0.0)));
+///   ];
+///
+///   // extensions are still available
+///   my_fancy_button + (BackgroundColor(Color::srgb(0.0, 0.0, 0.0)))
+///     .(move |_: Trigger<Pointer<Click>>, mut commands: Commands| { /* ... */ });
+/// 
+///   // insertions can be chained, each segment is applied in order
+///   my_entity + (Node::default()) + (BorderRadius::all(Val::Px(5.0)));
+/// }
+/// ```
+///
+/// An insertion can be named with `name = base + ...`, the name is then bound to the entity inserted
+/// on, the same way a spawned entity is named. This is mostly useful when `base` comes from outside
+/// the macro and should be referred to under another name.
+///
+/// ```rs
+/// fn highlight(mut commands: Commands, hovered: Entity) {
+///   spawn! { commands
+///     button = hovered + (BackgroundColor(c!(#333)));
+///
+///     (Text::new("Hovered"), ChildOf(button));
+///   }
+/// }
+/// ```
+///
+/// ## Removal
+///
+/// Removal is the opposite of insertion, `name - (A, B)` removes the components of the given types from
+/// an existing entity. The entity must be named and spawned in advance, and removal is only allowed at
+/// the top level.
+///
+/// ```rs, no_run
+/// fn unhover(mut commands: Commands, button: Entity) {
+///   spawn! { commands
+///     // remove both components from `button`
+///     button - (BackgroundColor, BorderColor);
+///
+///     // a single type works as well
+///     button - (Outline);
+///   }
+/// }
+/// ```
+///
+/// The whole entity is despawned with `- name`. Since Bevy 0.16, despawning always takes the
+/// descendants along, so `-- name`, the recursive despawn of older versions, is the same.
+///
+/// ```rs
+/// fn rebuild(mut commands: Commands, panel: Entity) {
+///   spawn! { commands
+///     - panel;
+///
+///     (Node::default()).[
+///       (Text::new("Rebuilt"));
+///     ];
+///   }
+/// }
+/// ```
+///
+/// ## Replacing
+///
+/// `replace name definition` at the top level despawns the entity bound to `name` and spawns a new one
+/// from the definition, which is then bound to `name` instead. The old entity is despawned first, so
+/// the new one never coexists with it. Its children are despawned along with it rather than moved to
+/// the new entity, the new entity has to spawn its own children.
+///
+/// ```rs, no_run
+/// fn rebuild(mut commands: Commands, panel: Entity, score: u32) {
+///   spawn! { commands
+///     replace panel (Node::default()).[
+///       (Text::new(format!("Score: {score}")));
+///     ];
+///
+///     // `panel` is now the new entity
+///     (Text::new("Footer"), ChildOf(panel));
+///   }
+/// }
+/// ```
+///
+/// ## Code block injection
+///
+/// Since the entities inside the macro is enclosed within a generated scope to prevent the namespace
+/// pollution, code block injection makes it possible to execute code without leaving the macro.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   entity_1 (Button);
+///   entity_2 (Button);
+///
+///   {
+///     println!("This is inside a code block!");
+///     println!("{entity_1:?}");
+///     println!("{entity_2:?}");
+///
+///     // you can do whatever you want here, just make sure the ownership of spawner will not be taken
+///     // if you want to spawn any entities after this code block
+///   };
+/// }
+/// ```
+///
+/// ## Global observer
+///
+/// `observe (Event) closure` at the top level spawns a global observer, which isn't tied to any entity
+/// and reacts to the event no matter which entity it targets. The first parameter of the closure is
+/// typed as `Trigger<Event>` automatically, unless it already has a type.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   observe (Pointer<Click>) |trigger, names: Query<&Name>| {
+///     if let Ok(name) = names.get(trigger.target()) {
+///       println!("{name} is clicked!");
+///     }
+///   };
+///
+///   (Button, Name::new("Button"));
+/// }
+/// ```
+///
+/// `observe (...)` followed by anything but a closure is still an entity named `observe`.
+///
+/// ## Let statement
+///
+/// A single binding doesn't need a whole code block, `let` statements can be written directly at the
+/// top level, in children groups and in flow bodies. They are emitted as-is, so the binding is
+/// visible to everything after it within the same scope. `spawner`, `entity`, `this` and `parent` are
+/// used by the generated code, and can't be bound this way.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   let color = c!(#fff);
+///
+///   (Text::new("Hello"), TextColor(color));
+///   (Text::new("World"), TextColor(color));
+/// }
+/// ```
+///
+/// ## Attributes
+///
+/// Outer attributes can be put in front of any top level or child, most usefully `#[cfg(...)]` to
+/// compile a whole entity out. The attributes are applied to every statement the macro generates for
+/// it. Like with any conditionally compiled `let`, a name declared by an entity which is compiled out
+/// can't be used by what's compiled in.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   #[cfg(debug_assertions)]
+///   debug_panel (Node::default()).[
+///     (Text::new("Debug"));
+///   ];
+///
+///   (Node::default()).[
+///     #[cfg(feature = "fps")] (Text::new("FPS"));
+///     (Text::new("Menu"));
+///   ];
+/// }
+/// ```
+///
+/// ## Collecting entities
+///
+/// Names in the macro aren't visible after it. `spawner => name` binds `name` after the macro to an
+/// array of every named top level entity, in the order they are spawned. Entities named inside
+/// children groups or flow bodies are left out.
+///
+/// ```rs, no_run
+/// spawn! { commands => menu;
+///   title (Text::new("Menu"));
+///   panel (Node::default()).[
+///     (Button, Text::new("Start"));
+///   ];
+/// }
+///
+/// // later on, despawn the whole menu at once
+/// for entity in menu {
+///   commands.entity(entity).despawn();
+/// }
+/// ```
+///
+/// ## Returning the entity
+///
+/// When the last top level entity is marked with `return`, the macro evaluates to its `Entity`, so it
+/// can be used as an expression. Only a spawned entity can be returned, and it has to be the last one.
+/// Without `return`, the macro is a statement and evaluates to `()`.
+///
+/// ```rs, no_run
+/// fn setup(mut commands: Commands) -> Entity {
+///   let root = spawn! { commands
+///     return (Node::default()).[
+///       (Text::new("Hello, World!"));
+///     ];
+///   };
+///
+///   spawn! { commands
+///     (Text::new("Footer"), ChildOf(root));
+///     return (Node::default())
+///   }
+/// }
+/// ```
+///
+/// ## Extension
+///
+/// An entity can be extended with any number of:
+///
+/// 1. Method Call
+/// 1. Code Block
+/// 1. Relationship
+///
+/// All extensions are started with `.` after the entity definition.
+///
+/// ### Method Call
+///
+/// Method call is a call to a method of `EntityCommands`. The auto completion is supported for the
+/// method name and the arguments.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button)
+///     .observe(|_: Trigger<Pointer<Click>>| { println!("Hello, World!"); })
+///     .[(Text::new("Hello, World!"))];
+/// }
+/// ```
+///
+/// Since `observe` is most likely to be used, a shortcut is provided to omit the method name.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button)
+///     .(|_: Trigger<Pointer<Click>>| { println!("Hello, World!"); })
+///     .[(Text::new("Hello, World!"))];
+/// }
+/// ```
+///
+/// The argument is passed to `observe` untouched, so anything `observe` accepts works, including
+/// function items and generic `impl IntoObserverSystem` parameters.
+///
+/// ```rs, no_run
+/// fn on_click(trigger: Trigger<Pointer<Click>>, names: Query<&Name>) {
+///   println!("{:?} is clicked!", names.get(trigger.target()));
+/// }
+///
+/// fn button<E: Event, B: Bundle, M>(mut commands: Commands, cb: impl IntoObserverSystem<E, B, M>) {
+///   spawn! { commands
+///     (Button, Name::new("Function")).(on_click);
+///     (Button, Name::new("Generic" )).(cb);
+///   }
+/// }
+/// ```
+///
+/// Several observers can be given at once, separated by commas. Each of them is passed to its own
+/// `observe` call, in order.
+///
+/// ```rs
+/// spawn! { commands
+///   // same as `.(on_click).(on_hover)`
+///   (Button).(on_click, on_hover);
+/// }
+/// ```
+///
+/// The event can be given with `.<Event>(...)`, `.observe::<Event>(...)` or `.on::<Event>(...)`, the
+/// first parameter of the closures is then typed as `Trigger<Event>`, unless it's typed already. The
+/// event is passed to each `observe` call as well, so an observer made by a generic function gets its
+/// event from there, and the event is written once for all of them.
+///
+/// ```rs
+/// spawn! { commands
+///   (Button)
+///     .<Pointer<Over>>(|trigger, mut commands: Commands| {
+///       commands.entity(trigger.target()).insert(BackgroundColor(c!(#333)));
+///     })
+///     .observe::<Pointer<Out>>(|trigger, mut commands: Commands| {
+///       commands.entity(trigger.target()).insert(BackgroundColor(c!(#000)));
+///     });
+/// }
+/// ```
+///
+/// ```rs, no_run
+/// fn recolor<E: Event>(color: Color) -> impl FnMut(Trigger<E>, Commands) {
+///   move |trigger, mut commands| { commands.entity(trigger.target()).insert(BackgroundColor(color)); }
+/// }
+///
+/// spawn! { commands
+///   // same as `.(recolor::<Pointer<Over>>(c!(#333))).(play_sound::<Pointer<Over>>)`
+///   (Button).on::<Pointer<Over>>(recolor(c!(#333)), play_sound);
+/// }
+/// ```
+///
+/// To reference the current entity, you can use `this` for `Entity` and `entity` for `EntityCommands`.
+/// `this` is a plain `Entity` bound before any extension, so it's `Copy` and can be moved into the
+/// observer closures with `move`, whether they insert on, modify or despawn the entity.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button, BackgroundColor(Color::srgb(0.0, 0.0, 0.0)))
+///     .(move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+///       commands.entity(this).insert(BackgroundColor(Color::srgb(1.0, 1.0, 1.0)));
+///     })
+///     .[(Text::new("Hello, World!"))];
+/// }
+/// ```
+///
+/// `.this(...)` saves moving `this` by hand, the first parameter of each closure is given the current
+/// entity and the rest are the parameters of the observer. The closures are made `move` for this.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button, BackgroundColor(Color::srgb(0.0, 0.0, 0.0)))
+///     // same as `.(move |_: Trigger<Pointer<Click>>, mut commands: Commands| ...)` using `this`
+///     .this(|button, _: Trigger<Pointer<Click>>, mut commands: Commands| {
+///       commands.entity(button).insert(BackgroundColor(Color::srgb(1.0, 1.0, 1.0)));
+///     });
+/// }
+/// ```
+///
+/// A method call can be made conditional by adding `_if` to the method name, the first argument will
+/// then be used as the condition and the rest are passed to the method. This works for the methods of
+/// `EntityCommands` and the extensions of the macro, like `observe_global` or `with`. `insert_if` and
+/// `try_insert_if` are already provided by `EntityCommands`, so they are left untouched, as is any
+/// other method ending with `_if`, e.g. of an extension trait.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, clickable: bool) {
+///   spawn! { commands
+///     (Button)
+///       // generates `if clickable { entity.observe(...); }`
+///       .observe_if(clickable, |_: Trigger<Pointer<Click>>| { println!("Hello, World!"); });
+///   }
+/// }
+/// ```
+///
+/// To insert some components only when a condition holds, use `.?(condition) { (components) }`. The
+/// condition is evaluated after the entity is spawned, so `this` and `entity` can be used in it.
+///
+/// ```rs
+/// fn foo(mut commands: Commands, selected: bool) {
+///   spawn! { commands
+///     (Button)
+///       // generates `if selected { entity.insert((Outline::default(), BackgroundColor(c!(#333)))); }`
+///       .?(selected) { (Outline::default(), BackgroundColor(c!(#333))) };
+///   }
+/// }
+/// ```
+///
+/// When the component is already an `Option`, `.insert_opt(option)` inserts what's inside only when
+/// it's `Some`, which saves wrapping the whole entity in an `if let` for a single optional component.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, tint: Option<BackgroundColor>) {
+///   spawn! { commands
+///     // generates `if let Some(bundle) = (tint) { entity.insert(bundle); }`
+///     (Button).insert_opt(tint);
+///   }
+/// }
+/// ```
+///
+/// The observers added with `.(...)` or `.observe(...)` are scoped to the current entity, they only
+/// react to the events targeting `this`. To react to the events of all entities, use `observe_global`,
+/// which registers the observer with `Commands::add_observer` instead. This requires the spawner to be
+/// `Commands`.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button)
+///     // only triggered when this button is clicked
+///     .(|_: Trigger<Pointer<Click>>| { println!("This button is clicked!"); })
+///     // triggered when anything is clicked
+///     .observe_global(|_: Trigger<Pointer<Click>>| { println!("Something is clicked!"); });
+/// }
+/// ```
+///
+/// The observer itself is an entity too. To get hold of it, e.g. to despawn it later to turn the
+/// interaction off, give it a name with `.name = (...)`. The name is declared in front of the entity,
+/// so it can be referenced after the entity just like a named entity. This requires the spawner to be
+/// `Commands`.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button)
+///     .on_click = (|_: Trigger<Pointer<Click>>| { println!("Hello, World!"); });
+///
+///   {
+///     // stop reacting to the clicks
+///     commands.entity(on_click).despawn();
+///   };
+/// }
+/// ```
+///
+/// ### Code Block
+///
+/// Code block is a block of code that will be executed in the context of the entity. As previously
+/// mentioned, the code block can also access `this` and `entity` variables.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button)
+///     .{
+///       // print the entity id of the current entity
+///       println!("{this:?}");
+///
+///       // manually adding a child
+///       entity.with_child((Text::new("Hello, World!")));
+///     };
+/// }
+/// ```
+///
+/// Inside the code block, `entity` is a reborrow of the entity, so it can be passed by value without
+/// affecting the rest of the definition. With `Commands` it's an `EntityCommands`, while with `World` it's
+/// a `&mut EntityWorldMut`, since `EntityWorldMut` can't be reborrowed.
+///
+/// When the spawner takes the `commands` binding itself, like `commands`, `[commands.reborrow()]` or
+/// `[&mut commands]`, that `commands` can't be used in the code block. The code block gets the `Commands`
+/// of the entity as `commands` instead, to spawn unrelated entities or change resources. It's taken from
+/// `entity`, so `entity` can't be used before `commands` is done with, use `commands.entity(this)` to mix
+/// them. With any other spawner, like `World`, a `commands` of your own is left as it is, the world is
+/// reached with `entity.world_scope(...)`.
+///
+/// ```rs
+/// spawn! { commands
+///   (Button)
+///     .{
+///       commands.insert_resource(ClearColor(Color::BLACK));
+///       commands.spawn(Text::new("Spawned aside"));
+///
+///       // `commands` isn't used anymore, `entity` can be used again
+///       entity.insert(BackgroundColor(Color::WHITE));
+///     };
+/// }
+/// ```
+///
+/// When the logic is factored into a function, `.with(f)` passes the same reborrowed entity to `f`
+/// instead, which avoids writing a code block only to forward `entity`. `.apply(f)` is an alias of it.
+///
+/// ```rs, no_run
+/// fn style_button(mut entity: EntityCommands) {
+///   entity.insert((BackgroundColor(Color::BLACK), BorderRadius::MAX));
+/// }
+///
+/// spawn! { commands
+///   (Button)
+///     .with(style_button)
+///     .apply(|mut entity| { entity.insert(Node::default()); });
+/// }
+/// ```
+///
+/// ### Relationship
+///
+/// Parenting is just a special case of Bevy's relationships. Any other relationship can be attached with
+/// `related`, the target must be an `Entity`. The relationship component is constructed through
+/// `Relationship::from`, so a type which is not a relationship will be reported as such.
+///
+/// ```rs, no_run
+/// #[derive(Component)]
+/// #[relationship(relationship_target = Debtors)]
+/// struct Owes(Entity);
+///
+/// spawn! { commands
+///   bank (Bank);
+///
+///   // equivalent to `entity.insert(Owes(bank))`
+///   (Person).related::<Owes>(bank);
+/// }
+/// ```
+///
+/// ## Children Group
+///
+/// Children group is a group of entities quoted by `[]` after the `.`. The entities in the group will
+/// be spawned as children of the parent entity. One entity can have multiple children groups, but all
+/// of them have to be after the extensions. This is because the `spawner` ownership will be temporarily
+/// taken for method calls and code blocks, to prevent this from happening, the children group is forced
+/// to be the last part of the entity definition. Within the same group, the entities can reference
+/// each other, but entities in 2 different groups under same parent can't.
+///
+/// Extensions can still follow the children groups, the entity is then obtained again from the
+/// spawner with `this` before they are applied. However, no more children group can be added after
+/// them.
+///
+/// A lone child which is neither named nor extended doesn't need a whole group, `.child(bundle)` is
+/// an extension spawning `bundle` as a child with `with_child`.
+///
+/// ```rs
+/// spawn! { commands
+///   // same as `(Button).[(Text::new("Play"))]`
+///   (Button).child(Text::new("Play"));
+/// }
+/// ```
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button)
+///     .[(Text::new("Hello, World!"))]
+///     // applied after the children are spawned
+///     .insert(BackgroundColor(c!(#f00)));
+/// }
+/// ```
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   (Button)
+///     .[
+///       a (Text::new("Hello, World!"));
+///       b (Text::new("Hello, World!"));
+///
+///       {
+///         // code block injection is also possible
+///         // you can access `a` and `b` here
+///       };
+///     ]
+///     .[
+///       c (Text::new("Hello, World!"));
+///
+///       {
+///         // you can't access `a`, `b`, but `c`
+///       };
+///     ];
+/// }
+/// ```
+///
+/// Referencing a name which is only declared in another group is an error of the macro, pointing at
+/// the reference and explaining why the name can't be seen. The same applies to the names declared in
+/// flow bodies, which are only visible within the body. The names bound by the pattern of a flow, like
+/// `item` of `for item in ...`, are visible in its body, and so are the names bound inside the code
+/// itself, like closure parameters. The macro can't see the bindings from outside of it, so such a
+/// binding can't share its name with an entity of another group, rename the entity then.
+///
+/// `pre a, b;` forward declares names in a children group, like `let a;` in Rust. An entity named
+/// after one of them, directly in the group or in a flow body of it, assigns the declared name instead
+/// of binding a new one, and so do a named `if` and `name = base + ...`. This lets an entity picked by a
+/// flow statement be referenced after it. As with `let a;`, the name can only be read once it's assigned
+/// on every path.
+///
+/// ```rs
+/// fn menu(mut commands: Commands, wide: bool) {
+///   spawn! { commands
+///     (Node::default()).[
+///       pre title;
+///
+///       if wide {
+///         title (Text::new("Main Menu"));
+///       } else {
+///         title (Text::new("Menu"));
+///       }
+///
+///       {
+///         println!("{title:?}");
+///       };
+///     ];
+///   }
+/// }
+/// ```
+///
+/// A whole collection of bundles can be spawned at once with `...expr`, where `expr` is anything
+/// implementing `IntoIterator` whose items are bundles. Each bundle is spawned as a child of the parent,
+/// in order. Splats are only allowed in children groups, and the entities they spawn aren't named.
+///
+/// ```rs
+/// fn list(mut commands: Commands, items: Vec<String>) {
+///   spawn! { commands
+///     (Node::default()).[
+///       (Text::new("Header"));
+///       ...items.into_iter().map(Text::new);
+///     ];
+///   }
+/// }
+/// ```
+///
+/// By default the children are appended to the parent. To place them somewhere else, use
+/// `children_at(index)` in front of the group. The entities of the group are spawned in order, then all
+/// of them are moved to `index` with their relative order kept. Only the entities directly inside the
+/// group and the ones spawned by splats are moved, the ones spawned by flow statements are still
+/// appended. This requires the spawner to be `Commands`.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   list (Node::default()).[
+///     (Text::new("Second"));
+///     (Text::new("Third"));
+///   ];
+///
+///   list + ().children_at(0)[
+///     (Text::new("First"));
+///   ];
+/// }
+/// ```
+///
+/// With the `spawn-related` feature enabled, a children group which only contains plain entities,
+/// i.e. no name, extension, `..bundle`, `+component`, flow statement or code block, and whose own
+/// children groups are plain as well, is spawned with the `Children::spawn` bundle introduced in Bevy
+/// 0.16, instead of spawning every child with `ChildOf` on its own. Other groups are spawned as usual.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   // inserts `Children::spawn((Spawn((Text::new("A"),)), Spawn((Text::new("B"),))))`
+///   (Node::default()).[
+///     (Text::new("A"));
+///     (Text::new("B"));
+///   ];
+/// }
+/// ```
+///
+/// ## Flow Control
+///
+/// `if`, `if_let`, `for`, `while`, `while_let`, `loop`, and `match` are supported in the macro. The syntax is similar
+/// to the Rust syntax, but the body is in the DSL this macro provides. The flow control can be used
+/// in the top level and children group.
+///
+/// The loops are generated as real Rust loops and code blocks are inserted as plain blocks, never
+/// wrapped in a closure. So `break` and `continue` inside a code block, or inside a code block
+/// extension, target the enclosing loop as well. The same goes for `break` and `continue` placed in
+/// the body of an `if` or `if let`, no matter how deeply they are nested, they always target the
+/// innermost loop around them. Such a `break` or `continue` with no loop of the macro around it is an
+/// error, even when the macro itself is inside of a loop.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, hidden: usize) {
+///   spawn! { commands
+///     for i in 0..10 {
+///       {
+///         if i == hidden { continue; }
+///       };
+///
+///       (Text::new(format!("Number: {i}")));
+///     }
+///   }
+/// }
+/// ```
+///
+/// The loops can be labeled as well, so `break` and `continue` in a nested loop can target an outer
+/// one.
+///
+/// ```rs, no_run
+/// fn grid(mut commands: Commands, cells: Vec<Vec<Option<Cell>>>) {
+///   spawn! { commands
+///     'grid: for row in cells {
+///       for cell in row {
+///         if let Some(cell) = cell {
+///           (cell);
+///         } else {
+///           // a missing cell aborts the whole grid
+///           break 'grid;
+///         }
+///       }
+///     }
+///   }
+/// }
+/// ```
+///
+/// ### If
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, number: i32) {
+///   spawn! { commands
+///     if number > 0 {
+///       (Text::new("It's positive!"));
+///     }
+///
+///     // you can also use `else if` statement
+///     else if number < 0 {
+///       (Text::new("It's negative!"));
+///     }
+///
+///     // or just `else`
+///     else {
+///       (Text::new("It's zero!"));
+///     }
+///   }
+/// }
+/// ```
+///
+/// ### If Let
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, number: Option<u32>) {
+///   spawn! { commands
+///     if let None = number {
+///       (Text::new("No number!"));
+///     }
+///
+///     // `else for` is also supported, but it must be the last control flow
+///     // in fact, `else for`, `else while`, `else while let` are all supported
+///     // but they must be the last else statement
+///     else for i in 0..number.unwrap() {
+///       (Text::new(format!("Number: {i}")), Node {
+///         top: v!({i as f32 * 20.0}px),
+///         ..Default::default()
+///       });
+///     }
+///   }
+/// }
+/// ```
+///
+/// ### For
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, number: i32) {
+///   spawn! { commands
+///     for i in 0..number {
+///       if i == 100 {
+///         // break is supported
+///         break;
+///       }
+///
+///       (Text::new(format!("Number: {i}")), Node {
+///        top: v!({i as f32 * 20.0}px),
+///        ..Default::default()
+///       });
+///     }
+///   }
+/// }
+/// ```
+///
+/// A name declared in a loop body names the entity spawned by the current iteration, and like any
+/// other name declared in a flow body, it can't be referenced after the loop. To keep all of them,
+/// collect them with a `let` statement and a code block.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands) {
+///   spawn! { commands
+///     let mut cells = vec![];
+///
+///     for i in 0..9 {
+///       cell (Text::new(format!("Cell {i}")));
+///       { cells.push(cell); };
+///     }
+///
+///     { println!("{cells:?}"); };
+///   }
+/// }
+/// ```
+///
+/// When a loop spawns a lot of plain entities, mark it with `for!` to spawn all of them at once with
+/// `spawn_batch`, which is faster than spawning them one by one. This only applies when the body is a
+/// single entity, optionally given a parent, without a name, an extension, a children group, a
+/// `..bundle` spread or a `+component`, and not referring to `this`. Otherwise, the loop is generated as
+/// a regular one. The iterator is mapped by a `move` closure that has to be `'static`, so the values
+/// used by the components are moved into it.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands) {
+///   spawn! { commands
+///     (Node::default()).[
+///       for! i in 0..1000 {
+///         #"Cell {i}" (Text::new(format!("{i}")));
+///       }
+///     ];
+///   }
+/// }
+/// ```
+///
+/// ### While
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, number: i32) {
+///   spawn! { commands
+///     while number > 0 {
+///       if number % 3 == 1 {
+///         // continue is supported
+///         continue;
+///       }
+///
+///       (Text::new(format!("Number: {number}")), Node {
+///         top: v!({number as f32 * 20.0}px),
+///         ..Default::default()
+///       });
+///     }
+///   }
+/// }
+/// ```
+///
+/// ### While Let
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, string: String) {
+///   spawn! { commands
+///     while let Some(c) = string.pop() {
+///       (Text::new(format!("Char: {c}")), Node {
+///         top: v!({string.len() as f32 * 20.0}px),
+///         ..Default::default()
+///       });
+///     }
+///   }
+/// }
+/// ```
+///
+/// ### Loop
+///
+/// `loop` spawns until a `break` is reached, which is useful when the condition to stop is only known
+/// inside the body.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, mut width: f32) {
+///   spawn! { commands
+///     loop {
+///       { width /= 2.0; }
+///
+///       if width < 10.0 {
+///         break;
+///       }
+///
+///       (Node { width: v!({width}px), ..Default::default() });
+///     }
+///   }
+/// }
+/// ```
+///
+/// ### Match
+///
+/// Each arm of a `match` is a body of its own, so it's wrapped in `{}` like the other flows. Guards are
+/// kept as-is.
+///
+/// ```rs, no_run
+/// enum Reward { Coins(u32), Item(String), Nothing }
+///
+/// fn foo(mut commands: Commands, reward: Reward) {
+///   spawn! { commands
+///     match reward {
+///       Reward::Coins(n) if n > 100 => {
+///         (Text::new("Jackpot!"));
+///       }
+///       Reward::Coins(n) => {
+///         (Text::new(format!("{n} coins")));
+///       }
+///       Reward::Item(name) => {
+///         (Text::new(name));
+///       }
+///       Reward::Nothing => {}
+///     }
+///   }
+/// }
+/// ```
+///
+/// ### Named If
+///
+/// An `if` or `if let` can be named when each of its branches spawns exactly one entity, the name is
+/// then bound to the entity spawned by the branch that is taken. It must end with an `else`, so the
+/// name is always bound, and the entities in the branches can't be named on their own. A loop spawns
+/// any number of entities, so naming `for` or `while` is an error.
+///
+/// ```rs, no_run
+/// fn foo(mut commands: Commands, dark: bool) {
+///   spawn! { commands
+///     icon if dark {
+///       (ImageNode::new(dark_icon))
+///     } else {
+///       (ImageNode::new(light_icon))
+///     };
+///
+///     button (Button);
+///     button > icon;
+///   }
+/// }
+/// ```
+///
+/// ## Entity markers
+///
+/// With the `entity-markers` feature enabled, every spawned entity block starts with a no-op
+/// `const _: &str = "entity: <name>";` item, spanned to the entity's name or label. It has no effect at
+/// runtime, but makes it easy to find which part of the expanded code (e.g. from `cargo expand`)
+/// belongs to which entity. Unnamed and unlabeled entities are marked as `<anonymous>`.
+///
+/// ```rs, no_run
+/// spawn! { commands
+///   root (Node::default()).[
+///     #"Title" (Text::new("Hello"));
+///   ];
+/// }
+///
+/// // expands to something like
+/// {
+///   const _: &str = "entity: root";
+///   ...
+///   {
+///     const _: &str = "entity: Title";
+///     ...
+///   }
+/// }
+/// ```
+///
+/// # Grammar
+///
+/// * `<TOKEN>*` means repeat 0-inf times separated by `TOKEN`, the last `TOKEN` is optional.
+///
+/// ```txt
+/// spawn        ::= spawner ('=>' name)? (top_level | ';')* ('return' top_level ';'*)? ;
+///
+/// definition   ::= '(' ('..' argument ','?)* ('+'? (shorthand ':')? component)<','>* ')' ('.' extension)* ('.' children)* ('.' extension)* ;
+/// entity       ::= ('#' name | name? label?) definition ;
+///
+/// parented     ::= parent '>' entity | entity '<' parent ;
+/// parent       ::= name | '[' EXPR ']' ;
+/// chosen       ::= name ('if' ('let' PAT '=')? EXPR '{' entity '}' 'else')+ '{' entity '}' ;
+/// reparented   ::= name '>' name ;
+/// removed      ::= name '-' '(' TYPE<','>+ ')' ;
+/// despawned    ::= '-' '-'? name ;
+/// observer     ::= 'observe' '(' TYPE ')' CLOSURE ;
+/// replaced     ::= 'replace' name label? definition ;
+/// inserted     ::= (name '=')? name ('+' definition)+ ;
+/// splat        ::= '...' EXPR ;
+/// pre          ::= 'pre' name<','>+ ;
+/// bare         ::= EXPR <<<a path, a call of a path with '::' or a struct literal, followed by ';' or the end>>> ;
+///
+/// child        ::= OUTER_ATTRIBUTE* (entity | inserted | chosen | flow<child    > | code_block | let | splat | pre) ;
+/// top_level    ::= OUTER_ATTRIBUTE* (entity | bare | inserted | chosen | flow<top_level> | code_block | parented | reparented | removed | despawned | replaced | observer | let) ;
+///
+/// extension    ::= observe | captured | method_call | code_block | related | conditional ;
+/// observe      ::= ('<' TYPE '>' | ('observe' | 'on') '::' '<' TYPE '>')? '(' argument<','>+ ')' ;
+/// captured     ::= name '=' '(' argument ')' ;
+/// children     ::= ('children_at' '(' argument ')')? '[' (child | ';')* ']' ;
+/// method_call  ::= name '(' argument<','>* ')' ;
+/// related      ::= 'related' '::' '<' TYPE '>' '(' argument ')' ;
+/// conditional  ::= '?' '(' argument ')' '{' '(' component<','>* ')' '}' ;
+///
+/// flow     <T> ::= if<T> | if_let<T> | for<T> | while<T> | while_let<T> | loop<T> | match<T> ;
+/// control  <T> ::= 'break' LIFETIME? | 'continue' LIFETIME? | T | ';' ;
+/// if       <T> ::= 'if' EXPR '{' control<T>* '}' ('else' else<T>)?;
+/// if_let   <T> ::= 'if' 'let' PAT '=' EXPR '{' control<T>* '}' ('else' else<T>)?;
+/// else     <T> ::= flow<T> | '{' control<T>* '}' ;
+/// for      <T> ::= loop_label? 'for' '!'? PAT in EXPR '{' control<T>* '}' ;
+/// while    <T> ::= loop_label? 'while' EXPR '{' control<T>* '}' ;
+/// while_let<T> ::= loop_label? 'while' 'let' PAT '=' EXPR '{' control<T>* '}' ;
+/// loop     <T> ::= loop_label? 'loop' '{' control<T>* '}' ;
+/// match    <T> ::= 'match' EXPR '{' (PAT ('if' EXPR)? '=>' '{' control<T>* '}')<','>* '}' ;
+///
+/// name         ::= IDENT ;
+/// label        ::= '#' STRING ;
+/// loop_label   ::= LIFETIME ':' ;
+/// shorthand    ::= <<<with `component-shorthand`>>> 'bg' | 'border' | 'color' ;
+/// spawner      ::= IDENT ('.' MEMBER)* | '[' '&' 'mut' EXPR ']' | '[' EXPR 'reborrow'? ']' | '@' | <<<nothing, with `default-spawner`>>> ;
+/// argument     ::= EXPR ;
+/// component    ::= EXPR ;
+/// code_block   ::= EXPR_BLOCK ;
+/// let          ::= LET_STATEMENT ;
+/// ```
+#[proc_macro]
+pub fn spawn(input: TokenStream) -> TokenStream {
+  apply::<bevy_toolbox_parser::spawn::Spawn>(input, true)
+}
+
+
+/// This macro is used to extend an existing entity with the definition syntax of `spawn!`.
+///
+/// The target is the `EntityCommands` of the entity, either as an identifier, a field, or an expression
+/// wrapped with `[]`. What follows is the same as `base + (...)` in `spawn!`, so the components,
+/// extensions and children groups are all supported, and more definitions can be chained with `+`.
+/// The spawner of the children is the `Commands` of the target.
+///
+/// ```rs, no_run
+/// fn new_button(mut entity: EntityCommands, text: &str) {
+///   insert! { entity
+///     (Button, Node::default())
+///       .(|_: Trigger<Pointer<Click>>| println!("Clicked!"))
+///       .[(Text::new(text))];
+///   }
+/// }
+/// ```
+///
+/// # Grammar
+///
+/// ```txt
+/// insert ::= target definition ('+' definition)* ';'? ;
+/// target ::= IDENT ('.' MEMBER)* | '[' EXPR ']' ;
+/// ```
+///
+/// `definition` is the same as the one of `spawn!`.
+#[proc_macro]
+pub fn insert(input: TokenStream) -> TokenStream {
+  apply::<bevy_toolbox_parser::spawn::Insert>(input, false)
+}
+
+
+/// This macro is used to simplify the creation of the bevy's `Val` enum.
+///
+/// # Syntax
+///
+/// There are 7 variants of the `Val` enum, and 2 syntax for each non-auto variant. In total, there
+/// are 13 syntax.
+///
+/// * `+` in between tokens means there can be no space between them.
+///
+/// 1. `Val::Auto`    - `auto`, `@`
+/// 1. `Val::Percent` - `number '%'` *space is optional* (e.g. `10%`)
+/// 1. `Val::Px`      - `number + 'px'` (e.g. `10px`)
+/// 1. `Val::Vw`      - `number + 'vw'` (e.g. `10vw`)
+/// 1. `Val::Vh`      - `number + 'vh'` (e.g. `10vh`)
+/// 1. `Val::Vmin`    - `number + 'vmin'` (e.g. `10vmin`)
+/// 1. `Val::Vmax`    - `number + 'vmax'` (e.g. `10vmax`)
+/// 1. `Val::Percent` - `{EXPR} + '%'` (e.g. `{10.0 + 20.0}%`)
+/// 1. `Val::Px`      - `{EXPR} + 'px'` (e.g. `{10.0 + 20.0}px`)
+/// 1. `Val::Vw`      - `{EXPR} + 'vw'` (e.g. `{10.0 + 20.0}vw`)
+/// 1. `Val::Vh`      - `{EXPR} + 'vh'` (e.g. `{10.0 + 20.0}vh`)
+/// 1. `Val::Vmin`    - `{EXPR} + 'vmin'` (e.g. `{10.0 + 20.0}vmin`)
+/// 1. `Val::Vmax`    - `{EXPR} + 'vmax'` (e.g. `{10.0 + 20.0}vmax`)
+///
+/// ```rs, no_run
+/// v!(auto);
+/// v!(@);
+/// v!(10%);
+/// v!(10px);
+/// v!({10.0 + 20.0}px);
+/// v!(10 vw); // space not allowed, error will be thrown
+/// ```
+///
+/// The syntax without an expression can also be written in a string literal, which expands to the
+/// same `Val`. The errors in the string are reported on the string itself.
+///
+/// ```rs, no_run
+/// v!("10px"); // same as `v!(10px)`
+/// v!("50%");
+/// v!("auto");
+/// ```
+///
+/// # Grammar
+///
+/// ```txt
+/// v ::=
+///   | 'auto'
+///   | '@'
+///   | number '%'
+///   | number + 'px'
+///   | number + 'vw'
+///   | number + 'vh'
+///   | number + 'vmin'
+///   | number + 'vmax'
+///   | '{' EXPR '}' '%'
+///   | '{' EXPR '}' 'px'
+///   | '{' EXPR '}' 'vw'
+///   | '{' EXPR '}' 'vh'
+///   | '{' EXPR '}' 'vmin'
+///   | '{' EXPR '}' 'vmax'
+///   | STRING // any of the above without EXPR
+///   ;
+///
+/// number ::= INT | FLOAT ;
+/// ```
+#[proc_macro]
+pub fn v(input: TokenStream) -> TokenStream {
+  apply::<bevy_toolbox_parser::value::Value>(input, false)
+}
+
+
+/// This macro is used to simplify the creation of the bevy's `Color` enum.
+///
+/// # Syntax
+///
+/// The syntax is mimicking the CSS color syntax. The macro fully supports the auto completion for the
+/// color names and the color spaces.
+///
+/// ## Hex
+///
+/// Hex colors are color codes that starts with `#` followed by 3, 4, 6, or 8 hex characters. The digits
+/// are not case sensitive, so `#fff` is equivalent to `#FFF`. The color will be converted to `srgb`
+/// color space.
+///
+/// * 3 hex digits - `#rgb`       (equivalent to `#rrggbb`)
+/// * 4 hex digits - `#rgba`      (equivalent to `#rrggbbaa`)
+/// * 6 hex digits - `#rrggbb`
+/// * 8 hex digits - `#rrggbbaa`
+///
+/// ```rs, no_run
+/// c!(#fff);
+/// c!(#62a7ff);
+/// ```
+///
+/// ## Functional Notation
+///
+/// All functional notations can have 3 or 4 arguments. The 4th argument is the alpha channel, if not
+/// provided, it will be set to `1.0`.
+///
+/// * `srgb`   - Standard RGB color space
+/// * `linear` - Linear RGB color space
+/// * `hsl`    - Hue, Saturation, Lightness
+/// * `hsv`    - Hue, Saturation, Value
+/// * `hwb`    - Hue, Whiteness, Blackness
+/// * `lab`    - L\*a\*b\* color space
+/// * `lch`    - Luminance, Chroma, Hue
+/// * `oklab`  - Oklab color space
+/// * `oklch`  - Oklch color space
+/// * `xyz`    - CIE 1931 XYZ color space
+///
+/// With the function selected, follow it with the arguments in the parentheses.
+///
+/// ```rs, no_run
+/// c!(srgb(1.0, 0.5, 0.5));
+/// c!(linear(1.0, 0.5, 0.5));
+/// ```
+///
+/// ## CSS Named Colors
+///
+/// There are 149 named colors in CSS. The auto completion is supported for all of them. The color will
+/// be converted to `srgb` color space.
+///
+/// ```rs, no_run
+/// c!(firebrick);
+/// c!(darkolivegreen);
+/// ```
+///
+/// The named colors come with the `css-colors` feature, which is enabled by default. Without it, the
+/// table of names isn't compiled into the macro, and a named color is reported as an error.
+///
+/// ## No wrap
+///
+/// The `c!` macro by default will wrap the color with `Color` enum, but sometimes you might just want
+/// the inner value that color represents. To do this, simply adding `!` before the color.
+///
+/// ```rs, no_run
+/// c!( #fff); // Color::Srgba(Srgba::new(1.0, 1.0, 1.0, 1.0))
+/// c!(!#000); //              Srgba::new(0.0, 0.0, 0.0, 1.0)
+/// ```
+///
+/// # Grammar
+///
+/// ```txt
+/// c ::= '!'? color;
+///
+/// color ::=
+///   | '#' + hex{3}    // #rgb
+///   | '#' + hex{4}    // #rgba
+///   | '#' + hex{6}    // #rrggbb
+///   | '#' + hex{8}    // #rrggbbaa
+///   | 'srgb'   '(' number<','>{3, 4} ')'
+///   | 'linear' '(' number<','>{3, 4} ')'
+///   | 'hsl'    '(' number<','>{3, 4} ')'
+///   | 'hsv'    '(' number<','>{3, 4} ')'
+///   | 'hwb'    '(' number<','>{3, 4} ')'
+///   | 'lab'    '(' number<','>{3, 4} ')'
+///   | 'lch'    '(' number<','>{3, 4} ')'
+///   | 'oklab'  '(' number<','>{3, 4} ')'
+///   | 'oklch'  '(' number<','>{3, 4} ')'
+///   | 'xyz'    '(' number<','>{3, 4} ')'
+///   // too many to list here
+///   | <<<149 CSS named colors, with `css-colors`>>>
+///   ;
+///
+/// hex    ::= '0'..'9' | 'a'..'f' | 'A'..'F' ;
+/// number ::= INT | FLOAT ;
+/// ```
+#[proc_macro]
+pub fn c(input: TokenStream) -> TokenStream {
+  apply::<bevy_toolbox_parser::color::Color>(input, false)
+}
+
+
+/// This macro is used to simplify the creation of the bevy's `UiRect` struct.
+///
+/// # Syntax
+///
+/// Within the macro, you can specify 1-4 values separated by space. The values will be used for the
+/// top, right, bottom, and left sides of the `UiRect`. Each value can be a `Val` or `_` for default.
+/// It basically follows how CSS sides selection works.
+///
+/// ```rs, no_run
+/// e!(10px);                     // all sides are 10px
+/// e!(10px 20px);                // top and bottom are 10px, right and left are 20px
+/// e!(10px 20px 30px);           // top is 10px, right and left are 20px, bottom is 30px
+/// e!(10px 20px 30px 40px);      // top is 10px, right is 20px, bottom is 30px, left is 40px
+/// e!(10px 20px 30px 40px 50px); // error, only 4 values are allowed
+/// ```
+///
+/// An omitted side is `Val::default()`, which is `Val::Auto` rather than the `Val::Px(0.0)` of
+/// `UiRect::default()`. Like in CSS, the difference matters for margins, an `auto` margin takes up the
+/// free space, e.g. `e!(0px _)` centers horizontally, and `e!(0px)` doesn't.
+///
+/// ```rs, no_run
+/// e!(10px _ 20px); // top is 10px, right and left are `Val::Auto`, bottom is 20px
+/// ```
+///
+/// The sides can also be named with the `logical` mode, where `start` is the left side and `end` is
+/// the right one, along with `top` and `bottom`. The sides which aren't named are omitted. It's only a
+/// vocabulary, Bevy doesn't flip `start` and `end` for right-to-left layouts.
+///
+/// ```rs, no_run
+/// e!(logical: start 10px, end 5px); // left is 10px, right is 5px, top and bottom are `Val::Auto`
+/// e!(logical: top 10px, start 5px); // top is 10px, left is 5px, right and bottom are `Val::Auto`
+/// ```
+///
+/// # Grammar
+///
+/// * `<TOKEN>*` means repeat 0-inf times separated by `TOKEN`, the last `TOKEN` is optional.
+///
+/// ```txt
+/// e ::= val_or_omit{1,4} | 'logical' ':' (side val_or_omit)<','>* ;
+///
+/// val_or_omit ::= v | '_';
+/// side        ::= 'start' | 'end' | 'top' | 'bottom' ;
+/// ```
+#[proc_macro]
+pub fn e(input: TokenStream) -> TokenStream {
+  apply::<bevy_toolbox_parser::edges::Edges>(input, false)
+}
+
+/// This macro is used to simplify the creation of the bevy's `Node` struct with CSS-like properties.
+///
+/// # Syntax
+///
+/// Within the macro, you can specify `property: value;` pairs. The unspecified fields of the `Node`
+/// keep their default values, and an unknown property errors with the closest known one.
+///
+/// ```rs, no_run
+/// style! {
+///   width          : 100%;
+///   padding        : 10px 20px;
+///   flex-direction : row-reverse;
+///   justify-content: space-between;
+/// }
+///
+/// // is equivalent to
+///
+/// Node {
+///   width          : Val::Percent(100.),
+///   padding        : UiRect::new(Val::Px(20.), Val::Px(20.), Val::Px(10.), Val::Px(10.)),
+///   flex_direction : FlexDirection::RowReverse,
+///   justify_content: JustifyContent::SpaceBetween,
+///   ..Default::default()
+/// }
+/// ```
+///
+/// The value of a property is written the same as in `v!`, `e!`, or as a kebab-case variant of the
+/// field's enum.
+///
+/// | Property                                                | Value                    |
+/// | ------------------------------------------------------- | ------------------------ |
+/// | `width`, `height`, `min-width`, `min-height`            | `v!`                     |
+/// | `max-width`, `max-height`, `row-gap`, `column-gap`      | `v!`                     |
+/// | `padding`, `margin`, `border`                           | `e!`                     |
+/// | `display`                                               | `Display` variant        |
+/// | `position-type`                                         | `PositionType` variant   |
+/// | `flex-direction`                                        | `FlexDirection` variant  |
+/// | `flex-wrap`                                             | `FlexWrap` variant       |
+/// | `justify-content`                                       | `JustifyContent` variant |
+/// | `align-items`                                           | `AlignItems` variant     |
+/// | `align-content`                                         | `AlignContent` variant   |
+///
+/// # Grammar
+///
+/// * `<TOKEN>*` means repeat 0-inf times separated by `TOKEN`, the last `TOKEN` is optional.
+///
+/// ```txt
+/// style ::= (property ':' value)<';'>* ;
+///
+/// property ::= IDENT<'-'>+ ;
+/// value    ::= v | e | IDENT<'-'>+ ;
+/// ```
+#[proc_macro]
+pub fn style(input: TokenStream) -> TokenStream {
+  apply::<bevy_toolbox_parser::style::Style>(input, false)
+}
+
+
+fn apply<P: Parse+Generate>(input: TokenStream, allow_empty: bool) -> TokenStream {
+  if input.is_empty() {
+    if allow_empty {
+      return TokenStream::new();
+    }
+
+    return Error::new(
+      Span::call_site(),
+      "This macro can't be called without any input",
+    ).to_compile_error().into();
+  }
+
+  match bevy_toolbox_parser::parse_whole::<P>.parse(input) {
+    Ok(value) => value.generate(),
+    Err(err) => err.to_compile_error(),
+  }.into()
+}
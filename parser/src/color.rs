@@ -0,0 +1,487 @@
+//! # Grammar
+//!
+//! * `<TOKEN>*` means repeat 0-inf times separated by `TOKEN`, the last `TOKEN` is optional.
+//!
+//! ```txt
+//! c ::= '!'? color;
+//!
+//! color ::=
+//!   | '#' + hex{3}    // #rgb
+//!   | '#' + hex{4}    // #rgba
+//!   | '#' + hex{6}    // #rrggbb
+//!   | '#' + hex{8}    // #rrggbbaa
+//!   | 'srgb'   '(' number<','>{3, 4} ')'
+//!   | 'linear' '(' number<','>{3, 4} ')'
+//!   | 'hsl'    '(' number<','>{3, 4} ')'
+//!   | 'hsv'    '(' number<','>{3, 4} ')'
+//!   | 'hwb'    '(' number<','>{3, 4} ')'
+//!   | 'lab'    '(' number<','>{3, 4} ')'
+//!   | 'lch'    '(' number<','>{3, 4} ')'
+//!   | 'oklab'  '(' number<','>{3, 4} ')'
+//!   | 'oklch'  '(' number<','>{3, 4} ')'
+//!   | 'xyz'    '(' number<','>{3, 4} ')'
+//!   // too many to list here
+//!   | <<<149 CSS named colors, with `css-colors`>>>
+//!   ;
+//!
+//! hex    ::= '0'..'9' | 'a'..'f' | 'A'..'F' ;
+//! number ::= INT | FLOAT ;
+//! ```
+use crate::*;
+
+
+#[derive(Clone)]
+pub enum Color {
+  Srgba     (Span, bool, (f32, f32, f32, f32)),
+  LinearRgba(Span, bool, (f32, f32, f32, f32)),
+  Hsla      (Span, bool, (f32, f32, f32, f32)),
+  Hsva      (Span, bool, (f32, f32, f32, f32)),
+  Hwba      (Span, bool, (f32, f32, f32, f32)),
+  Laba      (Span, bool, (f32, f32, f32, f32)),
+  Lcha      (Span, bool, (f32, f32, f32, f32)),
+  Oklaba    (Span, bool, (f32, f32, f32, f32)),
+  Oklcha    (Span, bool, (f32, f32, f32, f32)),
+  Xyza      (Span, bool, (f32, f32, f32, f32)),
+  Hex       (Span, Span, bool, (f32, f32, f32, f32)),
+  Css       (Span, bool, &'static str, (f32, f32, f32, f32)),
+  Unfinished(Option<Ident>),
+}
+
+impl Parse for Color {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let mut no_wrap = false;
+
+    if input.peek(Token![!]) {
+      input.parse::<Token![!]>()?;
+      no_wrap = true;
+    }
+
+    if input.peek(Token![#]) {
+      let hash = input.parse::<Token![#]>()?;
+      let span = input.span();
+
+      // the hex can be ident if starts with a letter
+      // or a literal if starts with a number
+      let hex = if input.peek(Ident) {
+        let token = input.parse::<Ident>()?;
+        token.to_string()
+      } else if input.peek(LitInt) {
+        let token = input.parse::<LitInt>()?;
+        token.span().source_text().unwrap_or("000".to_string())
+      } else {
+        return Err(input.error("Expected 3, 4, 6 or 8 hex digits"));
+      };
+
+      // make sure all digits are hex
+      if hex.chars().any(|c| !c.is_ascii_hexdigit()) {
+        return Err(Error::new(span, "Expected 3, 4, 6 or 8 hex digits"));
+      }
+
+      // make sure the length is correct
+      if hex.len() != 3 && hex.len() != 4 && hex.len() != 6 && hex.len() != 8 {
+        return Err(Error::new(span, "Expected 3, 4, 6 or 8 hex digits"));
+      }
+
+      // simplified hex parsing
+      if hex.len() <= 4 {
+        fn hex_to_f32(hex: char) -> f32 { hex.to_digit(16).unwrap() as f32 * 0x11 as f32 / 0xff as f32 }
+
+        let mut chars = hex.chars();
+
+        return Ok(Color::Hex(span, hash.span, no_wrap, (
+          hex_to_f32(chars.next().unwrap()),
+          hex_to_f32(chars.next().unwrap()),
+          hex_to_f32(chars.next().unwrap()),
+          hex_to_f32(chars.next().unwrap_or('f')))));
+      }
+
+      // full hex parsing
+      fn hex_to_f32(hex: &str) -> f32 { u8::from_str_radix(hex, 16).unwrap() as f32 / 0xff as f32 }
+
+      return Ok(Color::Hex(span, hash.span, no_wrap, (
+        hex_to_f32(hex.get(0..2).unwrap()),
+        hex_to_f32(hex.get(2..4).unwrap()),
+        hex_to_f32(hex.get(4..6).unwrap()),
+        hex_to_f32(hex.get(6..8).unwrap_or("ff")))));
+    }
+
+    if input.peek(Ident) {
+      let token = input.parse::<Ident>()?;
+
+      match token.to_string().as_str() {
+        code @ ("srgb" | "linear" | "hsl" | "hsv" | "hwb" | "lab" | "lch" | "oklab" | "oklch" | "xyz") => {
+          if !input.peek(Paren) {
+            return Err(input.error("Expected parenthesis"));
+          }
+
+          let content;
+          parenthesized!(content in input);
+          let components: Vec<f32> = content.parse_terminated(|s| {
+            if s.peek(LitFloat) { return Ok(s.parse::<LitFloat>()?.base10_parse()?); }
+            if s.peek(LitInt  ) { return Ok(s.parse::<LitInt  >()?.base10_parse()?); }
+            return Err(s.error("Expected float or integer"));
+          }, Token![,])?.into_iter().collect();
+
+          if components.len() != 3 && components.len() != 4 {
+            return Err(input.error("Expected 3 or 4 components"));
+          }
+
+          let a     = components.get(0).copied().unwrap_or(0.0);
+          let b     = components.get(1).copied().unwrap_or(0.0);
+          let c     = components.get(2).copied().unwrap_or(0.0);
+          let alpha = components.get(3).copied().unwrap_or(1.0);
+
+          match code {
+            "srgb"   => return Ok(Color::Srgba     (token.span(), no_wrap, (a, b, c, alpha))),
+            "linear" => return Ok(Color::LinearRgba(token.span(), no_wrap, (a, b, c, alpha))),
+            "hsl"    => return Ok(Color::Hsla      (token.span(), no_wrap, (a, b, c, alpha))),
+            "hsv"    => return Ok(Color::Hsva      (token.span(), no_wrap, (a, b, c, alpha))),
+            "hwb"    => return Ok(Color::Hwba      (token.span(), no_wrap, (a, b, c, alpha))),
+            "lab"    => return Ok(Color::Laba      (token.span(), no_wrap, (a, b, c, alpha))),
+            "lch"    => return Ok(Color::Lcha      (token.span(), no_wrap, (a, b, c, alpha))),
+            "oklab"  => return Ok(Color::Oklaba    (token.span(), no_wrap, (a, b, c, alpha))),
+            "oklch"  => return Ok(Color::Oklcha    (token.span(), no_wrap, (a, b, c, alpha))),
+            "xyz"    => return Ok(Color::Xyza      (token.span(), no_wrap, (a, b, c, alpha))),
+            _ => unreachable!()
+          }
+        }
+
+        #[cfg(feature = "css-colors")]
+        _ => if let Some(color) = css_color(&token, no_wrap) {
+          return Ok(color);
+        },
+
+        #[cfg(not(feature = "css-colors"))]
+        _ => {},
+      }
+
+      return Ok(Color::Unfinished(Some(token)));
+    }
+
+    Ok(Color::Unfinished(None))
+  }
+}
+
+impl Generate for Color {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let (kind, value, no_wrap) = match self {
+      Color::Srgba(span, no_wrap, (r, g, b, a)) => {
+        let mut value = quote_spanned! {span.clone()=> Srgba};
+        value.extend(quote! {::new(#r, #g, #b, #a)});
+        (quote! {Srgba}, value, no_wrap)
+      }
+
+      Color::LinearRgba(span, no_wrap, (r, g, b, a)) => {
+        let mut value = quote_spanned! {span.clone()=> LinearRgba};
+        value.extend(quote! {::new(#r, #g, #b, #a)});
+        (quote! {LinearRgba}, value, no_wrap)
+      }
+
+      Color::Hsla(span, no_wrap, (h, s, l, a)) => {
+        let mut value = quote_spanned! {span.clone()=> Hsla};
+        value.extend(quote! {::new(#h, #s, #l, #a)});
+        (quote! {Hsla}, value, no_wrap)
+      }
+
+      Color::Hsva(span, no_wrap, (h, s, v, a)) => {
+        let mut value = quote_spanned! {span.clone()=> Hsva};
+        value.extend(quote! {::new(#h, #s, #v, #a)});
+        (quote! {Hsva}, value, no_wrap)
+      }
+
+      Color::Hwba(span, no_wrap, (h, w, b, a)) => {
+        let mut value = quote_spanned! {span.clone()=> Hwba};
+        value.extend(quote! {::new(#h, #w, #b, #a)});
+        (quote! {Hwba}, value, no_wrap)
+      }
+
+      Color::Laba(span, no_wrap, (l, a, b, _a)) => {
+        let mut value = quote_spanned! {span.clone()=> Laba};
+        value.extend(quote! {::new(#l, #a, #b, #_a)});
+        (quote! {Laba}, value, no_wrap)
+      }
+
+      Color::Lcha(span, no_wrap, (l, c, h, a)) => {
+        let mut value = quote_spanned! {span.clone()=> Lcha};
+        value.extend(quote! {::new(#l, #c, #h, #a)});
+        (quote! {Lcha}, value, no_wrap)
+      }
+
+      Color::Oklaba(span, no_wrap, (l, a, b, _a)) => {
+        let mut value = quote_spanned! {span.clone()=> Oklaba};
+        value.extend(quote! {::new(#l, #a, #b, #_a)});
+        (quote! {Oklaba}, value, no_wrap)
+      }
+
+      Color::Oklcha(span, no_wrap, (l, c, h, a)) => {
+        let mut value = quote_spanned! {span.clone()=> Oklcha};
+        value.extend(quote! {::new(#l, #c, #h, #a)});
+        (quote! {Oklcha}, value, no_wrap)
+      }
+
+      Color::Xyza(span, no_wrap, (x, y, z, a)) => {
+        let mut value = quote_spanned! {span.clone()=> Xyza};
+        value.extend(quote! {::new(#x, #y, #z, #a)});
+        (quote! {Xyza}, value, no_wrap)
+      }
+
+      Color::Hex(span1, span2, no_wrap, (r, g, b, a)) => {
+        let mut value = quote! {use};
+        value.extend(quote_spanned! {span1.clone()=> Srgba});
+        value.extend(quote! {;});
+        value.extend(quote_spanned! {span2.clone()=> Srgba});
+        value.extend(quote! {::new(#r, #g, #b, #a)});
+        (quote! {Srgba}, quote! {{ #value }}, no_wrap)
+      }
+
+      Color::Css(span, no_wrap, c, (r, g, b, a)) => {
+        let doc = format!("\
+          **Hex** `{c}`\\\n\
+          **R**   `{r}`\\\n\
+          **G**   `{g}`\\\n\
+          **B**   `{b}`\\\n\
+          **A**   `{a}`");
+
+        // a unit const rather than an item type keeps the hover preview usable in const contexts
+        let name = Ident::new("COLOR_CODE", *span);
+
+        (quote! {Srgba}, quote! {{
+          #[doc = #doc]
+          const #name: () = ();
+          Srgba::new(#r, #g, #b, #a)
+        }}, no_wrap)
+      }
+
+      Color::Unfinished(name) => {
+        #[cfg(feature = "css-colors")]
+        let css = css_names();
+
+        // without the table, a name is reported rather than left to the autocomplete
+        #[cfg(not(feature = "css-colors"))]
+        let css = match name {
+          Some(name) if !FUNCTIONS.contains(&name.to_string().as_str()) => {
+            let error = format!("Unknown color `{name}`, the named CSS colors need the `css-colors` feature");
+            return Error::new(name.span(), error).to_compile_error();
+          },
+          _ => quote! {},
+        };
+
+        return quote! {{
+          #[allow(non_camel_case_types)]
+          enum PredefinedColor {
+            #css
+
+            srgb  (f32, f32, f32, f32),
+            linear(f32, f32, f32, f32),
+            hsl   (f32, f32, f32, f32),
+            hsv   (f32, f32, f32, f32),
+            hwb   (f32, f32, f32, f32),
+            lab   (f32, f32, f32, f32),
+            lch   (f32, f32, f32, f32),
+            oklab (f32, f32, f32, f32),
+            oklch (f32, f32, f32, f32),
+            xyz   (f32, f32, f32, f32),
+          }
+
+          PredefinedColor::#name
+        }}
+      }
+    };
+
+    if *no_wrap {
+      return quote! {{
+        use bevy::color::*;
+        #value
+      }}
+    }
+
+    quote! {{
+      use bevy::color::*;
+      Color::#kind(#value)
+    }}
+  }
+
+  fn generate_default() -> proc_macro2::TokenStream {
+    quote! {bevy::color::Color::default()}
+  }
+}
+
+
+/// The functional notations, which are variants of the autocomplete enum as well.
+#[cfg(not(feature = "css-colors"))]
+const FUNCTIONS: &[&str] = &["srgb", "linear", "hsl", "hsv", "hwb", "lab", "lch", "oklab", "oklch", "xyz"];
+
+/// A CSS named color, its hex code and its value.
+#[cfg(feature = "css-colors")]
+type CssColor = (&'static str, &'static str, (f32, f32, f32, f32));
+
+/// The CSS named colors, sorted by name for the lookup.
+#[cfg(feature = "css-colors")]
+const CSS_COLORS: &[CssColor] = &[
+  ("aliceblue"           , " #f0f8ff"   , (0.9411764705882353, 0.9725490196078431, 1.0               , 1.0)),
+  ("antiquewhite"        , " #faebd7"   , (0.9803921568627451, 0.9215686274509803, 0.8431372549019608, 1.0)),
+  ("aqua"                , " #00ffff"   , (0.0               , 1.0               , 1.0               , 1.0)),
+  ("aquamarine"          , " #7fffd4"   , (0.4980392156862745, 1.0               , 0.8313725490196079, 1.0)),
+  ("azure"               , " #f0ffff"   , (0.9411764705882353, 1.0               , 1.0               , 1.0)),
+  ("beige"               , " #f5f5dc"   , (0.9607843137254902, 0.9607843137254902, 0.8627450980392157, 1.0)),
+  ("bisque"              , " #ffe4c4"   , (1.0               , 0.8941176470588236, 0.7686274509803922, 1.0)),
+  ("black"               , " #000000"   , (0.0               , 0.0               , 0.0               , 1.0)),
+  ("blanchedalmond"      , " #ffebcd"   , (1.0               , 0.9215686274509803, 0.803921568627451 , 1.0)),
+  ("blue"                , " #0000ff"   , (0.0               , 0.0               , 1.0               , 1.0)),
+  ("blueviolet"          , " #8a2be2"   , (0.5411764705882353, 0.1686274509803921, 0.8862745098039215, 1.0)),
+  ("brown"               , " #a52a2a"   , (0.6470588235294118, 0.1647058823529411, 0.1647058823529411, 1.0)),
+  ("burlywood"           , " #deb887"   , (0.8705882352941177, 0.7215686274509804, 0.5294117647058824, 1.0)),
+  ("cadetblue"           , " #5f9ea0"   , (0.3725490196078431, 0.6196078431372549, 0.6274509803921569, 1.0)),
+  ("chartreuse"          , " #7fff00"   , (0.4980392156862745, 1.0               , 0.0               , 1.0)),
+  ("chocolate"           , " #d2691e"   , (0.8235294117647058, 0.4117647058823529, 0.1176470588235294, 1.0)),
+  ("coral"               , " #ff7f50"   , (1.0               , 0.4980392156862745, 0.3137254901960784, 1.0)),
+  ("cornflowerblue"      , " #6495ed"   , (0.3921568627450980, 0.5843137254901961, 0.9294117647058824, 1.0)),
+  ("cornsilk"            , " #fff8dc"   , (1.0               , 0.9725490196078431, 0.8627450980392157, 1.0)),
+  ("crimson"             , " #dc143c"   , (0.8627450980392157, 0.0784313725490196, 0.2352941176470588, 1.0)),
+  ("cyan"                , " #00ffff"   , (0.0               , 1.0               , 1.0               , 1.0)),
+  ("darkblue"            , " #00008b"   , (0.0               , 0.0               , 0.5450980392156862, 1.0)),
+  ("darkcyan"            , " #008b8b"   , (0.0               , 0.5450980392156862, 0.5450980392156862, 1.0)),
+  ("darkgoldenrod"       , " #b8860b"   , (0.7215686274509804, 0.5254901960784314, 0.0431372549019607, 1.0)),
+  ("darkgray"            , " #a9a9a9"   , (0.6627450980392157, 0.6627450980392157, 0.6627450980392157, 1.0)),
+  ("darkgreen"           , " #006400"   , (0.0               , 0.3921568627450980, 0.0               , 1.0)),
+  ("darkgrey"            , " #a9a9a9"   , (0.6627450980392157, 0.6627450980392157, 0.6627450980392157, 1.0)),
+  ("darkkhaki"           , " #bdb76b"   , (0.7411764705882353, 0.7176470588235294, 0.4196078431372549, 1.0)),
+  ("darkmagenta"         , " #8b008b"   , (0.5450980392156862, 0.0               , 0.5450980392156862, 1.0)),
+  ("darkolivegreen"      , " #556b2f"   , (0.3333333333333333, 0.4196078431372549, 0.1843137254901961, 1.0)),
+  ("darkorange"          , " #ff8c00"   , (1.0               , 0.5490196078431373, 0.0               , 1.0)),
+  ("darkorchid"          , " #9932cc"   , (0.6               , 0.1960784313725490, 0.8               , 1.0)),
+  ("darkred"             , " #8b0000"   , (0.5450980392156862, 0.0               , 0.0               , 1.0)),
+  ("darksalmon"          , " #e9967a"   , (0.9137254901960784, 0.5882352941176471, 0.4784313725490196, 1.0)),
+  ("darkseagreen"        , " #8fbc8f"   , (0.5607843137254902, 0.7372549019607844, 0.5607843137254902, 1.0)),
+  ("darkslateblue"       , " #483d8b"   , (0.2823529411764706, 0.2392156862745098, 0.5450980392156862, 1.0)),
+  ("darkslategray"       , " #2f4f4f"   , (0.1843137254901961, 0.3098039215686274, 0.3098039215686274, 1.0)),
+  ("darkslategrey"       , " #2f4f4f"   , (0.1843137254901961, 0.3098039215686274, 0.3098039215686274, 1.0)),
+  ("darkturquoise"       , " #00ced1"   , (0.0               , 0.807843137254902 , 0.8196078431372549, 1.0)),
+  ("darkviolet"          , " #9400d3"   , (0.5803921568627451, 0.0               , 0.8274509803921568, 1.0)),
+  ("deeppink"            , " #ff1493"   , (1.0               , 0.0784313725490196, 0.5764705882352941, 1.0)),
+  ("deepskyblue"         , " #00bfff"   , (0.0               , 0.7490196078431373, 1.0               , 1.0)),
+  ("dimgray"             , " #696969"   , (0.4117647058823529, 0.4117647058823529, 0.4117647058823529, 1.0)),
+  ("dimgrey"             , " #696969"   , (0.4117647058823529, 0.4117647058823529, 0.4117647058823529, 1.0)),
+  ("dodgerblue"          , " #1e90ff"   , (0.1176470588235294, 0.5647058823529412, 1.0               , 1.0)),
+  ("firebrick"           , " #b22222"   , (0.6980392156862745, 0.1333333333333333, 0.1333333333333333, 1.0)),
+  ("floralwhite"         , " #fffaf0"   , (1.0               , 0.9803921568627451, 0.9411764705882353, 1.0)),
+  ("forestgreen"         , " #228b22"   , (0.1333333333333333, 0.5450980392156862, 0.1333333333333333, 1.0)),
+  ("fuchsia"             , " #ff00ff"   , (1.0               , 0.0               , 1.0               , 1.0)),
+  ("gainsboro"           , " #dcdcdc"   , (0.8627450980392157, 0.8627450980392157, 0.8627450980392157, 1.0)),
+  ("ghostwhite"          , " #f8f8ff"   , (0.9725490196078431, 0.9725490196078431, 1.0               , 1.0)),
+  ("gold"                , " #ffd700"   , (1.0               , 0.8431372549019608, 0.0               , 1.0)),
+  ("goldenrod"           , " #daa520"   , (0.8549019607843137, 0.6470588235294118, 0.1254901960784313, 1.0)),
+  ("gray"                , " #808080"   , (0.5019607843137255, 0.5019607843137255, 0.5019607843137255, 1.0)),
+  ("green"               , " #008000"   , (0.0               , 0.5019607843137255, 0.0               , 1.0)),
+  ("greenyellow"         , " #adff2f"   , (0.6784313725490196, 1.0               , 0.1843137254901961, 1.0)),
+  ("grey"                , " #808080"   , (0.5019607843137255, 0.5019607843137255, 0.5019607843137255, 1.0)),
+  ("honeydew"            , " #f0fff0"   , (0.9411764705882353, 1.0               , 0.9411764705882353, 1.0)),
+  ("hotpink"             , " #ff69b4"   , (1.0               , 0.4117647058823529, 0.7058823529411765, 1.0)),
+  ("indianred"           , " #cd5c5c"   , (0.803921568627451 , 0.3607843137254902, 0.3607843137254902, 1.0)),
+  ("indigo"              , " #4b0082"   , (0.2941176470588235, 0.0               , 0.5098039215686274, 1.0)),
+  ("ivory"               , " #fffff0"   , (1.0               , 1.0               , 0.9411764705882353, 1.0)),
+  ("khaki"               , " #f0e68c"   , (0.9411764705882353, 0.9019607843137255, 0.5490196078431373, 1.0)),
+  ("lavender"            , " #e6e6fa"   , (0.9019607843137255, 0.9019607843137255, 0.9803921568627451, 1.0)),
+  ("lavenderblush"       , " #fff0f5"   , (1.0               , 0.9411764705882353, 0.9607843137254902, 1.0)),
+  ("lawngreen"           , " #7cfc00"   , (0.4862745098039215, 0.9882352941176471, 0.0               , 1.0)),
+  ("lemonchiffon"        , " #fffacd"   , (1.0               , 0.9803921568627451, 0.803921568627451 , 1.0)),
+  ("lightblue"           , " #add8e6"   , (0.6784313725490196, 0.8470588235294118, 0.9019607843137255, 1.0)),
+  ("lightcoral"          , " #f08080"   , (0.9411764705882353, 0.5019607843137255, 0.5019607843137255, 1.0)),
+  ("lightcyan"           , " #e0ffff"   , (0.8784313725490196, 1.0               , 1.0               , 1.0)),
+  ("lightgoldenrodyellow", " #fafad2"   , (0.9803921568627451, 0.9803921568627451, 0.8235294117647058, 1.0)),
+  ("lightgray"           , " #d3d3d3"   , (0.8274509803921568, 0.8274509803921568, 0.8274509803921568, 1.0)),
+  ("lightgreen"          , " #90ee90"   , (0.5647058823529412, 0.9333333333333333, 0.5647058823529412, 1.0)),
+  ("lightgrey"           , " #d3d3d3"   , (0.8274509803921568, 0.8274509803921568, 0.8274509803921568, 1.0)),
+  ("lightpink"           , " #ffb6c1"   , (1.0               , 0.7137254901960784, 0.7568627450980392, 1.0)),
+  ("lightsalmon"         , " #ffa07a"   , (1.0               , 0.6274509803921569, 0.4784313725490196, 1.0)),
+  ("lightseagreen"       , " #20b2aa"   , (0.1254901960784313, 0.6980392156862745, 0.6666666666666666, 1.0)),
+  ("lightskyblue"        , " #87cefa"   , (0.5294117647058824, 0.807843137254902 , 0.9803921568627451, 1.0)),
+  ("lightslategray"      , " #778899"   , (0.4666666666666667, 0.5333333333333333, 0.6               , 1.0)),
+  ("lightslategrey"      , " #778899"   , (0.4666666666666667, 0.5333333333333333, 0.6               , 1.0)),
+  ("lightsteelblue"      , " #b0c4de"   , (0.6901960784313725, 0.7686274509803922, 0.8705882352941177, 1.0)),
+  ("lightyellow"         , " #ffffe0"   , (1.0               , 1.0               , 0.8784313725490196, 1.0)),
+  ("lime"                , " #00ff00"   , (0.0               , 1.0               , 0.0               , 1.0)),
+  ("limegreen"           , " #32cd32"   , (0.1960784313725490, 0.803921568627451 , 0.1960784313725490, 1.0)),
+  ("linen"               , " #faf0e6"   , (0.9803921568627451, 0.9411764705882353, 0.9019607843137255, 1.0)),
+  ("magenta"             , " #ff00ff"   , (1.0               , 0.0               , 1.0               , 1.0)),
+  ("maroon"              , " #800000"   , (0.5019607843137255, 0.0               , 0.0               , 1.0)),
+  ("mediumaquamarine"    , " #66cdaa"   , (0.4               , 0.803921568627451 , 0.6666666666666666, 1.0)),
+  ("mediumblue"          , " #0000cd"   , (0.0               , 0.0               , 0.803921568627451 , 1.0)),
+  ("mediumorchid"        , " #ba55d3"   , (0.7294117647058823, 0.3333333333333333, 0.8274509803921568, 1.0)),
+  ("mediumpurple"        , " #9370db"   , (0.5764705882352941, 0.4392156862745098, 0.8588235294117647, 1.0)),
+  ("mediumseagreen"      , " #3cb371"   , (0.2352941176470588, 0.7019607843137254, 0.4431372549019607, 1.0)),
+  ("mediumslateblue"     , " #7b68ee"   , (0.4823529411764706, 0.4078431372549019, 0.9333333333333333, 1.0)),
+  ("mediumspringgreen"   , " #00fa9a"   , (0.0               , 0.9803921568627451, 0.6039215686274509, 1.0)),
+  ("mediumturquoise"     , " #48d1cc"   , (0.2823529411764706, 0.8196078431372549, 0.8               , 1.0)),
+  ("mediumvioletred"     , " #c71585"   , (0.7803921568627451, 0.0823529411764705, 0.5215686274509804, 1.0)),
+  ("midnightblue"        , " #191970"   , (0.0980392156862745, 0.0980392156862745, 0.4392156862745098, 1.0)),
+  ("mintcream"           , " #f5fffa"   , (0.9607843137254902, 1.0               , 0.9803921568627451, 1.0)),
+  ("mistyrose"           , " #ffe4e1"   , (1.0               , 0.8941176470588236, 0.8823529411764706, 1.0)),
+  ("moccasin"            , " #ffe4b5"   , (1.0               , 0.8941176470588236, 0.7098039215686275, 1.0)),
+  ("navajowhite"         , " #ffdead"   , (1.0               , 0.8705882352941177, 0.6784313725490196, 1.0)),
+  ("navy"                , " #000080"   , (0.0               , 0.0               , 0.5019607843137255, 1.0)),
+  ("oldlace"             , " #fdf5e6"   , (0.9921568627450981, 0.9607843137254902, 0.9019607843137255, 1.0)),
+  ("olive"               , " #808000"   , (0.5019607843137255, 0.5019607843137255, 0.0               , 1.0)),
+  ("olivedrab"           , " #6b8e23"   , (0.4196078431372549, 0.5568627450980392, 0.1372549019607843, 1.0)),
+  ("orange"              , " #ffa500"   , (1.0               , 0.6470588235294118, 0.0               , 1.0)),
+  ("orangered"           , " #ff4500"   , (1.0               , 0.2705882352941176, 0.0               , 1.0)),
+  ("orchid"              , " #da70d6"   , (0.8549019607843137, 0.4392156862745098, 0.8392156862745098, 1.0)),
+  ("palegoldenrod"       , " #eee8aa"   , (0.9333333333333333, 0.9098039215686274, 0.6666666666666666, 1.0)),
+  ("palegreen"           , " #98fb98"   , (0.596078431372549 , 0.984313725490196 , 0.596078431372549 , 1.0)),
+  ("paleturquoise"       , " #afeeee"   , (0.6862745098039216, 0.9333333333333333, 0.9333333333333333, 1.0)),
+  ("palevioletred"       , " #db7093"   , (0.8588235294117647, 0.4392156862745098, 0.5764705882352941, 1.0)),
+  ("papayawhip"          , " #ffefd5"   , (1.0               , 0.9372549019607843, 0.8352941176470589, 1.0)),
+  ("peachpuff"           , " #ffdab9"   , (1.0               , 0.8549019607843137, 0.7254901960784313, 1.0)),
+  ("peru"                , " #cd853f"   , (0.803921568627451 , 0.5215686274509804, 0.2470588235294117, 1.0)),
+  ("pink"                , " #ffc0cb"   , (1.0               , 0.7529411764705882, 0.796078431372549 , 1.0)),
+  ("plum"                , " #dda0dd"   , (0.8666666666666667, 0.6274509803921569, 0.8666666666666667, 1.0)),
+  ("powderblue"          , " #b0e0e6"   , (0.6901960784313725, 0.8784313725490196, 0.9019607843137255, 1.0)),
+  ("purple"              , " #800080"   , (0.5019607843137255, 0.0               , 0.5019607843137255, 1.0)),
+  ("rebeccapurple"       , " #663399"   , (0.4               , 0.2               , 0.6               , 1.0)),
+  ("red"                 , " #ff0000"   , (1.0               , 0.0               , 0.0               , 1.0)),
+  ("rosybrown"           , " #bc8f8f"   , (0.7372549019607844, 0.5607843137254902, 0.5607843137254902, 1.0)),
+  ("royalblue"           , " #4169e1"   , (0.2549019607843137, 0.4117647058823529, 0.8823529411764706, 1.0)),
+  ("saddlebrown"         , " #8b4513"   , (0.5450980392156862, 0.2705882352941176, 0.0745098039215686, 1.0)),
+  ("salmon"              , " #fa8072"   , (0.9803921568627451, 0.5019607843137255, 0.4470588235294118, 1.0)),
+  ("sandybrown"          , " #f4a460"   , (0.9568627450980393, 0.6431372549019608, 0.3764705882352941, 1.0)),
+  ("seagreen"            , " #2e8b57"   , (0.1803921568627451, 0.5450980392156862, 0.3411764705882353, 1.0)),
+  ("seashell"            , " #fff5ee"   , (1.0               , 0.9607843137254902, 0.9333333333333333, 1.0)),
+  ("sienna"              , " #a0522d"   , (0.6274509803921569, 0.3215686274509804, 0.1764705882352941, 1.0)),
+  ("silver"              , " #c0c0c0"   , (0.7529411764705882, 0.7529411764705882, 0.7529411764705882, 1.0)),
+  ("skyblue"             , " #87ceeb"   , (0.5294117647058824, 0.807843137254902 , 0.9215686274509803, 1.0)),
+  ("slateblue"           , " #6a5acd"   , (0.4156862745098039, 0.3529411764705882, 0.803921568627451 , 1.0)),
+  ("slategray"           , " #708090"   , (0.4392156862745098, 0.5019607843137255, 0.5647058823529412, 1.0)),
+  ("slategrey"           , " #708090"   , (0.4392156862745098, 0.5019607843137255, 0.5647058823529412, 1.0)),
+  ("snow"                , " #fffafa"   , (1.0               , 0.9803921568627451, 0.9803921568627451, 1.0)),
+  ("springgreen"         , " #00ff7f"   , (0.0               , 1.0               , 0.4980392156862745, 1.0)),
+  ("steelblue"           , " #4682b4"   , (0.2745098039215686, 0.5098039215686274, 0.7058823529411765, 1.0)),
+  ("tan"                 , " #d2b48c"   , (0.8235294117647058, 0.7058823529411765, 0.5490196078431373, 1.0)),
+  ("teal"                , " #008080"   , (0.0               , 0.5019607843137255, 0.5019607843137255, 1.0)),
+  ("thistle"             , " #d8bfd8"   , (0.8470588235294118, 0.7490196078431373, 0.8470588235294118, 1.0)),
+  ("tomato"              , " #ff6347"   , (1.0               , 0.3882352941176470, 0.2784313725490196, 1.0)),
+  ("transparent"         , "transparent", (0.0               , 0.0               , 0.0               , 0.0)),
+  ("turquoise"           , " #40e0d0"   , (0.2509803921568627, 0.8784313725490196, 0.8156862745098039, 1.0)),
+  ("violet"              , " #ee82ee"   , (0.9333333333333333, 0.5098039215686274, 0.9333333333333333, 1.0)),
+  ("wheat"               , " #f5deb3"   , (0.9607843137254902, 0.8705882352941177, 0.7019607843137254, 1.0)),
+  ("white"               , " #ffffff"   , (1.0               , 1.0               , 1.0               , 1.0)),
+  ("whitesmoke"          , " #f5f5f5"   , (0.9607843137254902, 0.9607843137254902, 0.9607843137254902, 1.0)),
+  ("yellow"              , " #ffff00"   , (1.0               , 1.0               , 0.0               , 1.0)),
+  ("yellowgreen"         , " #9acd32"   , (0.6039215686274509, 0.803921568627451 , 0.1960784313725490, 1.0)),
+];
+
+/// The CSS named color of the identifier, if any.
+#[cfg(feature = "css-colors")]
+fn css_color(token: &Ident, no_wrap: bool) -> Option<Color> {
+  let name  = token.to_string();
+  let index = CSS_COLORS.binary_search_by_key(&name.as_str(), |(name, ..)| name).ok()?;
+  let (_, code, rgba) = CSS_COLORS[index];
+
+  Some(Color::Css(token.span(), no_wrap, code, rgba))
+}
+
+/// The CSS named colors as the variants of the autocomplete enum.
+#[cfg(feature = "css-colors")]
+fn css_names() -> proc_macro2::TokenStream {
+  let names = CSS_COLORS.iter().map(|(name, ..)| Ident::new(name, Span::call_site()));
+  quote! { #(#names,)* }
+}
@@ -3,9 +3,10 @@
 //! * `<TOKEN>*` means repeat 0-inf times separated by `TOKEN`, the last `TOKEN` is optional.
 //!
 //! ```txt
-//! e ::= val_or_omit{1,4};
+//! e ::= val_or_omit{1,4} | 'logical' ':' (side val_or_omit)<','>* ;
 //!
 //! val_or_omit ::= v | '_';
+//! side        ::= 'start' | 'end' | 'top' | 'bottom' ;
 //! ```
 use crate::*;
 use value::*;
@@ -20,6 +21,10 @@ pub struct Edges {
 
 impl Parse for Edges {
   fn parse(input: ParseStream) -> Result<Self> {
+    if input.peek(Ident) && input.peek2(Token![:]) {
+      return Edges::parse_logical(input);
+    }
+
     let mut values = Vec::new();
 
     while !input.is_empty() && values.len() < 4 {
@@ -64,6 +69,55 @@ impl Parse for Edges {
   }
 }
 
+impl Edges {
+  /// `logical: start 10px, end 5px` names the sides, `start` is the left side and `end` is the right
+  /// one, the sides which aren't named are omitted.
+  fn parse_logical(input: ParseStream) -> Result<Self> {
+    let mode = input.parse::<Ident>()?;
+
+    if mode != "logical" {
+      return Err(Error::new(mode.span(), "Expected `logical` mode"));
+    }
+
+    input.parse::<Token![:]>()?;
+
+    let omit = || MightOmit::Omit(mode.span());
+    let mut sides: [Option<MightOmit<Value>>; 4] = [None, None, None, None];
+
+    while !input.is_empty() {
+      let side  = input.parse::<Ident>()?;
+      let index = match side.to_string().as_str() {
+        "top"    => 0,
+        "end"    => 1,
+        "bottom" => 2,
+        "start"  => 3,
+        _ => return Err(Error::new(side.span(), "Expected `start`, `end`, `top` or `bottom`")),
+      };
+
+      if sides[index].is_some() {
+        return Err(Error::new(side.span(), format!("`{side}` is already set")));
+      }
+
+      sides[index] = Some(input.parse()?);
+
+      if input.is_empty() {
+        break;
+      }
+
+      input.parse::<Token![,]>()?;
+    }
+
+    let [top, end, bottom, start] = sides;
+
+    Ok(Edges {
+      top   : top   .unwrap_or_else(omit),
+      right : end   .unwrap_or_else(omit),
+      bottom: bottom.unwrap_or_else(omit),
+      left  : start .unwrap_or_else(omit),
+    })
+  }
+}
+
 impl Generate for Edges {
   fn generate(&self) -> proc_macro2::TokenStream {
     let top    = self.top   .generate();
@@ -128,3 +182,29 @@ impl Generate for Edges {
     quote! {{ #result }}
   }
 }
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn expand(input: proc_macro2::TokenStream) -> String {
+    syn::parse2::<Edges>(input).unwrap().generate().to_string()
+  }
+
+  #[test]
+  fn omitted_sides_are_default() {
+    let default = "bevy :: ui :: Val :: default ()";
+
+    let edges = expand(quote! { 10px _ 20px });
+    assert!(edges.contains(&format!("right : {default}")));
+    assert!(edges.contains(&format!("left : {default}")));
+    assert!(edges.contains("top : bevy :: ui :: Val :: Px (10f32)"));
+    assert!(edges.contains("bottom : bevy :: ui :: Val :: Px (20f32)"));
+
+    let edges = expand(quote! { logical: start 10px });
+    assert!(edges.contains(&format!("top : {default}")));
+    assert!(edges.contains(&format!("right : {default}")));
+    assert!(edges.contains(&format!("bottom : {default}")));
+  }
+}
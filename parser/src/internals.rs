@@ -0,0 +1,51 @@
+//! Expansions of the macros from plain token streams, with the `internals` feature.
+//!
+//! These drive the same parsers and generators as the macros without going through the
+//! `#[proc_macro]` boundary, e.g. for snapshot tests of the expansions. They aren't covered by
+//! semver, and might change in any release.
+//!
+//! ```rs, no_run
+//! let tokens = bevy_toolbox_parser::internals::value(quote::quote! { 10px })?;
+//! assert_eq!(tokens.to_string(), "bevy :: ui :: Val :: Px (10f32)");
+//! ```
+use crate::*;
+
+
+/// Expands `spawn!`, an empty input expands to nothing like the macro.
+pub fn spawn(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+  if input.is_empty() {
+    return Ok(proc_macro2::TokenStream::new());
+  }
+
+  expand::<spawn::Spawn>(input)
+}
+
+/// Expands `insert!`.
+pub fn insert(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+  expand::<spawn::Insert>(input)
+}
+
+/// Expands `v!`.
+pub fn value(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+  expand::<value::Value>(input)
+}
+
+/// Expands `c!`.
+pub fn color(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+  expand::<color::Color>(input)
+}
+
+/// Expands `e!`.
+pub fn edges(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+  expand::<edges::Edges>(input)
+}
+
+/// Expands `style!`.
+pub fn style(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+  expand::<style::Style>(input)
+}
+
+
+fn expand<P: Parse+Generate>(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+  Ok(parse_whole::<P>.parse2(input)?.generate())
+}
@@ -0,0 +1,141 @@
+//! Parsers and generators behind the macros of `bevy_toolbox`.
+//!
+//! Every macro is a type implementing both [`Parse`] and [`Generate`], so other procedural macros
+//! can parse the same DSL from their own input and expand it in the same way.
+//!
+//! | Type                   | Macro     |
+//! | ---------------------- | --------- |
+//! | [`spawn::Spawn`]       | `spawn!`  |
+//! | [`spawn::Insert`]      | `insert!` |
+//! | [`value::Value`]       | `v!`      |
+//! | [`color::Color`]       | `c!`      |
+//! | [`edges::Edges`]       | `e!`      |
+//! | [`style::Style`]       | `style!`  |
+//!
+//! ```rs, no_run
+//! use bevy_toolbox_parser::{Generate, color::Color};
+//!
+//! #[proc_macro]
+//! pub fn tint(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+//!   let color = syn::parse_macro_input!(input as Color);
+//!   let color = color.generate();
+//!   quote::quote! { bevy::ui::BackgroundColor(#color) }.into()
+//! }
+//! ```
+//!
+//! With the `internals` feature, `internals` expands the macros straight from token streams.
+//!
+//! # Spans
+//!
+//! The generated paths carry the spans of the tokens they come from, so hovering or jumping to the
+//! definition of a token in the macro lands on what it expands to. The expected behaviour, to check
+//! by hand in an editor after changing a generator:
+//!
+//! | Input                          | Token           | Hover / go-to-definition                     |
+//! | ------------------------------ | --------------- | -------------------------------------------- |
+//! | `v!(10px)`, `v!(10vw)`, ...    | `10px`          | `Val::Px`, `Val::Vw`, ...                    |
+//! | `v!({x}px)`, `v!({x}vw)`, ...  | `px`, `vw`, ... | `Val::Px`, `Val::Vw`, ...                    |
+//! | `v!(10%)`, `v!({x}%)`          | `10`, `%`       | `Val::Percent`                               |
+//! | `v!(auto)`, `v!(@)`            | `auto`, `@`     | `Val::Auto`                                  |
+//! | `v!("10px")`                   | `"10px"`        | `Val::Px`                                    |
+//! | `c!(#fff)`                     | `#`, `fff`      | `Srgba`                                      |
+//! | `c!(srgb(...))`, ...           | `srgb`, ...     | `Srgba`, `LinearRgba`, `Hsla`, ...           |
+//! | `c!(firebrick)`                | `firebrick`     | the hex code and the channels                |
+//! | `c!(fire)`                     | `fire`          | the completions of the color names           |
+//! | `e!(10px 20px)`                | `10px`, `20px`  | the sides they set, and `Val::Px`            |
+//! | `style!(flex-direction: row)`  | `flex`, `row`   | `Node::flex_direction`, `FlexDirection::Row` |
+pub mod spawn;
+pub mod value;
+pub mod color;
+pub mod edges;
+pub mod turns;
+pub mod style;
+
+#[cfg(feature = "internals")]
+pub mod internals;
+
+use proc_macro2::Group;
+use proc_macro2::Span;
+use syn::*;
+use syn::parse::*;
+use syn::token::*;
+use quote::*;
+
+
+/// Turns a parsed DSL into the tokens of its expansion.
+pub trait Generate {
+  /// Generates the expansion.
+  fn generate(&self) -> proc_macro2::TokenStream;
+
+  /// Generates the expansion of an omitted value, for the types allowed in [`MightOmit`]. Types
+  /// without a default expand to a `compile_error!` rather than panicking in the macro.
+  fn generate_default() -> proc_macro2::TokenStream {
+    quote! { compile_error!("This value can't be omitted with `_`") }
+  }
+}
+
+
+/// Either a value or `_`, which falls back to [`Generate::generate_default`].
+#[derive(Clone)]
+pub enum MightOmit<T> {
+  Value(T),
+  Omit(Span),
+}
+
+impl<T: Parse> Parse for MightOmit<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    if input.peek(Token![_]) {
+      let token = input.parse::<Token![_]>()?;
+      return Ok(MightOmit::Omit(token.span));
+    }
+
+    Ok(MightOmit::Value(T::parse(input)?))
+  }
+}
+
+impl<T: Generate> Generate for MightOmit<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    match self {
+      MightOmit::Value(value) => value.generate(),
+      MightOmit::Omit(_) => T::generate_default(),
+    }
+  }
+
+  fn generate_default() -> proc_macro2::TokenStream {
+    T::generate_default()
+  }
+}
+
+
+/// Parses `P` from the whole input, the parsers stop at the end of what they understand, so the
+/// tokens left are reported as a whole instead of one by one.
+pub fn parse_whole<P: Parse>(input: ParseStream) -> Result<P> {
+  let value = P::parse(input)?;
+
+  if !input.is_empty() {
+    let rest = input.parse::<proc_macro2::TokenStream>()?;
+    return Err(Error::new_spanned(rest, "Unexpected token(s) after value"));
+  }
+
+  Ok(value)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct NoDefault;
+
+  impl Generate for NoDefault {
+    fn generate(&self) -> proc_macro2::TokenStream {
+      quote! {}
+    }
+  }
+
+  #[test]
+  fn missing_default_is_a_compile_error() {
+    let omitted = MightOmit::<NoDefault>::generate_default().to_string();
+    assert!(omitted.starts_with("compile_error !"), "{omitted}");
+  }
+}
@@ -0,0 +1,3933 @@
+//! # Grammar
+//!
+//! * `<TOKEN>*` means repeat 0-inf times separated by `TOKEN`, the last `TOKEN` is optional.
+//!
+//! ```txt
+//! spawn        ::= spawner ('=>' name)? (top_level | ';')* ('return' top_level ';'*)? ;
+//!
+//! definition   ::= '(' ('..' argument ','?)* ('+'? (shorthand ':')? component)<','>* ')' ('.' extension)* ('.' children)* ('.' extension)* ;
+//! entity       ::= ('#' name | name? label?) definition ;
+//!
+//! parented     ::= parent '>' entity | entity '<' parent ;
+//! parent       ::= name | '[' EXPR ']' ;
+//! chosen       ::= name ('if' ('let' PAT '=')? EXPR '{' entity '}' 'else')+ '{' entity '}' ;
+//! reparented   ::= name '>' name ;
+//! removed      ::= name '-' '(' TYPE<','>+ ')' ;
+//! despawned    ::= '-' '-'? name ;
+//! observer     ::= 'observe' '(' TYPE ')' CLOSURE ;
+//! replaced     ::= 'replace' name label? definition ;
+//! inserted     ::= (name '=')? name ('+' definition)+ ;
+//! splat        ::= '...' EXPR ;
+//! pre          ::= 'pre' name<','>+ ;
+//! bare         ::= EXPR <<<a path, a call of a path with '::' or a struct literal, followed by ';' or the end>>> ;
+//!
+//! child        ::= OUTER_ATTRIBUTE* (entity | inserted | chosen | flow<child    > | code_block | let | splat | pre) ;
+//! top_level    ::= OUTER_ATTRIBUTE* (entity | bare | inserted | chosen | flow<top_level> | code_block | parented | reparented | removed | despawned | replaced | observer | let) ;
+//!
+//! extension    ::= observe | captured | method_call | code_block | related | conditional ;
+//! observe      ::= ('<' TYPE '>' | ('observe' | 'on') '::' '<' TYPE '>')? '(' argument<','>+ ')' ;
+//! captured     ::= name '=' '(' argument ')' ;
+//! children     ::= ('children_at' '(' argument ')')? '[' (child | ';')* ']' ;
+//! method_call  ::= name '(' argument<','>* ')' ;
+//! related      ::= 'related' '::' '<' TYPE '>' '(' argument ')' ;
+//! conditional  ::= '?' '(' argument ')' '{' '(' component<','>* ')' '}' ;
+//!
+//! flow     <T> ::= if<T> | if_let<T> | for<T> | while<T> | while_let<T> | loop<T> | match<T> ;
+//! control  <T> ::= 'break' LIFETIME? | 'continue' LIFETIME? | T | ';' ;
+//! if       <T> ::= 'if' EXPR '{' control<T>* '}' ('else' else<T>)?;
+//! if_let   <T> ::= 'if' 'let' PAT '=' EXPR '{' control<T>* '}' ('else' else<T>)?;
+//! else     <T> ::= flow<T> | '{' control<T>* '}' ;
+//! for      <T> ::= loop_label? 'for' '!'? PAT in EXPR '{' control<T>* '}' ;
+//! while    <T> ::= loop_label? 'while' EXPR '{' control<T>* '}' ;
+//! while_let<T> ::= loop_label? 'while' 'let' PAT '=' EXPR '{' control<T>* '}' ;
+//! loop     <T> ::= loop_label? 'loop' '{' control<T>* '}' ;
+//! match    <T> ::= 'match' EXPR '{' (PAT ('if' EXPR)? '=>' '{' control<T>* '}')<','>* '}' ;
+//!
+//! name         ::= IDENT ;
+//! label        ::= '#' STRING ;
+//! loop_label   ::= LIFETIME ':' ;
+//! shorthand    ::= <<<with `component-shorthand`>>> 'bg' | 'border' | 'color' ;
+//! spawner      ::= IDENT ('.' MEMBER)* | '[' '&' 'mut' EXPR ']' | '[' EXPR 'reborrow'? ']' | '@' | <<<nothing, with `default-spawner`>>> ;
+//! argument     ::= EXPR ;
+//! component    ::= EXPR ;
+//! code_block   ::= EXPR_BLOCK ;
+//! let          ::= LET_STATEMENT ;
+//! ```
+use crate::*;
+use syn::ext::IdentExt;
+
+
+#[derive(Clone)]
+pub struct Spawn {
+  spawner  : Spawner,
+  collected: Option<Ident>,
+  top_level: Vec<TopLevel>,
+  /// The last top level is marked with `return`, the entity is the value of the macro.
+  returned : bool,
+}
+
+impl Parse for Spawn {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let spawner   = input.parse::<Spawner>()?;
+    let collected = if input.peek(Token![=>]) {
+      input.parse::<Token![=>]>()?;
+      Some(input.parse()?)
+    } else {
+      None
+    };
+
+    let mut top_level = vec![];
+    let mut returned  = false;
+
+    while !input.is_empty() {
+      if input.peek(Token![;]) {
+        input.parse::<Token![;]>()?;
+        continue;
+      }
+
+      if input.peek(Token![return]) {
+        let token = input.parse::<Token![return]>()?;
+        let entity = input.parse::<TopLevel>()?;
+
+        if !entity.is_spawned() {
+          return Err(Error::new(token.span, "Only a spawned entity can be returned"));
+        }
+
+        if collected.is_some() {
+          return Err(Error::new(token.span, "`return` can't be used together with `=> name`"));
+        }
+
+        while input.peek(Token![;]) {
+          input.parse::<Token![;]>()?;
+        }
+
+        if !input.is_empty() {
+          return Err(Error::new(token.span, "The returned entity has to be the last one"));
+        }
+
+        top_level.push(entity);
+        returned = true;
+        continue;
+      }
+
+      top_level.push(input.parse()?);
+    }
+
+    top_level.iter().try_for_each(|item| item.check_control(false))?;
+
+    if spawner.takes_commands() {
+      top_level.iter_mut().for_each(|item| item.visit_extensions(&mut Extension::rebind_commands));
+    }
+
+    Ok(Spawn { spawner, collected, top_level, returned })
+  }
+}
+
+impl Generate for Spawn {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Spawn { spawner, collected, top_level, returned } = self;
+
+    let mut content = Scope::check(top_level);
+    content.extend(spawner.generate());
+    let mut value   = None;
+
+    for (i, e) in top_level.iter().enumerate() {
+      // `return` is only allowed in front of the last top level, the entity is the value of the macro
+      if *returned && i == top_level.len() - 1 {
+        let mut e = e.clone();
+        value = e.returned_name();
+        content.extend(e.generate());
+        continue;
+      }
+
+      content.extend(e.generate());
+    }
+
+    // `spawner => name` binds the named top level entities after the macro, entities named inside
+    // children groups or flows are left out since their names aren't in scope at the end
+    if let Some(collected) = collected {
+      let names = top_level.iter().filter_map(|e| match e {
+        TopLevel::Entity  (entity  ) => entity.name.as_ref(),
+        TopLevel::Parented(parented) => parented.entity.name.as_ref(),
+        TopLevel::Replaced(replaced) => replaced.entity.name.as_ref(),
+        TopLevel::Chosen  (chosen  ) => Some(&chosen.name),
+        _                            => None,
+      }).fold(vec![], |mut names: Vec<&Ident>, name| {
+        // a name bound more than once refers to the last entity, listing it once is enough
+        if !names.contains(&name) {
+          names.push(name);
+        }
+
+        names
+      });
+
+      if names.is_empty() {
+        return Error::new(collected.span(), "no named top level entity to collect").to_compile_error();
+      }
+
+      return quote! { let #collected = { #content [#(#names),*] }; };
+    }
+
+    if let Some(value) = value {
+      return quote! { { #content #value } };
+    }
+
+    quote! { { #content }; }
+  }
+}
+
+
+/// `insert!` extends a single entity, given by its `EntityCommands`, as if it's `base + ...` in `spawn!`.
+#[derive(Clone)]
+pub struct Insert {
+  target  : Spawner,
+  inserted: Inserted,
+}
+
+impl Parse for Insert {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let target = if input.peek(Ident) || input.peek(Token![self]) {
+      Spawner::parse_place(input)?
+    } else if input.peek(Bracket) {
+      Spawner::Expr(input.parse::<Group>()?.stream())
+    } else {
+      return Err(input.error("Expected identifier or expression of `EntityCommands`"));
+    };
+
+    let mut definitions = vec![input.parse()?];
+    while input.peek(Token![+]) {
+      input.parse::<Token![+]>()?;
+      definitions.push(input.parse()?);
+    }
+
+    if input.peek(Token![;]) {
+      input.parse::<Token![;]>()?;
+    }
+
+    let base         = Ident::new("base", Span::mixed_site());
+    let mut inserted = Inserted { name: None, base, definitions, declared: false };
+    inserted.check_control(false)?;
+
+    if target.takes_commands() {
+      inserted.visit_extensions(&mut Extension::rebind_commands);
+    }
+
+    Ok(Insert { target, inserted })
+  }
+}
+
+impl Generate for Insert {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Insert { target, inserted } = self;
+
+    let errors = Scope::check(&[TopLevel::Inserted(inserted.clone())]);
+
+    let handle = Ident::new("target", Span::mixed_site());
+    let target = match target {
+      Spawner::Ident(ident) => quote! { let #handle = &mut #ident; },
+      Spawner::Field(field) => quote! { let #handle = &mut #field; },
+      Spawner::Expr (expr ) => quote! {
+        let mut #handle = (#expr);
+        let #handle = &mut #handle;
+      },
+      _ => unreachable!(),
+    };
+
+    let base    = &inserted.base;
+    let content = inserted.generate();
+
+    quote! {{
+      #errors
+      #target
+      let #base = #handle.id();
+      let mut spawner = #handle.commands();
+      let spawner = &mut spawner;
+      #content
+    };}
+  }
+}
+
+
+#[derive(Clone)]
+enum Spawner {
+  Ident   (Ident),
+  Field   (proc_macro2::TokenStream),
+  Expr    (proc_macro2::TokenStream),
+  Reborrow(proc_macro2::TokenStream),
+  /// `[&mut expr]`, the reference is used as is.
+  Ref     (proc_macro2::TokenStream),
+  Entity  (Token![@]),
+}
+
+impl Parse for Spawner {
+  fn parse(input: ParseStream) -> Result<Self> {
+    if input.peek(Ident) || input.peek(Token![self]) {
+      Spawner::parse_place(input)
+    } else if input.peek(Bracket) {
+      let token  = input.parse::<Group>()?;
+      let tokens = token.stream().into_iter().collect::<Vec<_>>();
+
+      // `[&mut expr]` is already a reference, only the reference is moved
+      if let [proc_macro2::TokenTree::Punct(and), proc_macro2::TokenTree::Ident(mut_), ..] = tokens.as_slice() {
+        if and.as_char() == '&' && mut_ == "mut" {
+          return Ok(Spawner::Ref(token.stream()));
+        }
+      }
+
+      // `[expr reborrow]` reborrows the expression instead of moving it
+      if let [expr @ .., prev, proc_macro2::TokenTree::Ident(last)] = tokens.as_slice() {
+        let is_method = matches!(prev, proc_macro2::TokenTree::Punct(p) if p.as_char() == '.');
+
+        if last == "reborrow" && !is_method {
+          let mut expr = expr.iter().cloned().collect::<proc_macro2::TokenStream>();
+          expr.extend([prev.clone()]);
+          return Ok(Spawner::Reborrow(expr));
+        }
+      }
+
+      Ok(Spawner::Expr(token.stream()))
+    } else if input.peek(Token![@]) {
+      Ok(Spawner::Entity(input.parse()?))
+    } else if cfg!(feature = "default-spawner") {
+      Ok(Spawner::Ident(Ident::new("commands", Span::call_site())))
+    } else if input.peek(Paren)         || input.peek(Brace)         || input.peek(Token![#])
+           || input.peek(Token![let])   || input.peek(Token![if])    || input.peek(Token![for])
+           || input.peek(Token![while]) || input.peek(Token![loop])  || input.peek(Token![match])
+           || input.peek(Lifetime)
+    {
+      // an entity where the spawner should be, the spawner was most likely forgotten
+      Err(input.error(
+        "missing spawner: `spawn!` needs a `Commands`/`World` (or `[expr]`) before the first entity",
+      ))
+    } else {
+      Err(input.error("Expected identifier or expression"))
+    }
+  }
+}
+
+impl Spawner {
+  /// `name` or a field access like `self.commands`, anything else, like a method call, needs `[]`.
+  fn parse_place(input: ParseStream) -> Result<Self> {
+    let root = input.call(Ident::parse_any)?;
+
+    if !input.peek(Token![.]) {
+      return Ok(Spawner::Ident(root));
+    }
+
+    let mut field = quote! { #root };
+    while input.peek(Token![.]) && (input.peek2(Ident) || input.peek2(LitInt)) {
+      let dot    = input.parse::<Token![.]>()?;
+      let member = input.parse::<Member>()?;
+      field.extend(quote! { #dot #member });
+    }
+
+    Ok(Spawner::Field(field))
+  }
+
+  /// Whether the spawner borrows or moves the `commands` binding, e.g. `commands` or
+  /// `[commands.reborrow()]`, which can't be used by the code blocks in the macro then.
+  fn takes_commands(&self) -> bool {
+    fn refers(tokens: &proc_macro2::TokenStream) -> bool {
+      let mut after_dot = false;
+
+      tokens.clone().into_iter().any(|token| {
+        let found = match &token {
+          proc_macro2::TokenTree::Ident(ident) => !after_dot && ident == "commands",
+          proc_macro2::TokenTree::Group(group) => refers(&group.stream()),
+          _                                    => false,
+        };
+
+        after_dot = matches!(token, proc_macro2::TokenTree::Punct(ref punct) if punct.as_char() == '.');
+        found
+      })
+    }
+
+    match self {
+      Spawner::Ident   (ident ) => ident == "commands",
+      Spawner::Field   (tokens)
+      | Spawner::Expr    (tokens)
+      | Spawner::Reborrow(tokens)
+      | Spawner::Ref     (tokens) => refers(tokens),
+      Spawner::Entity  (_     ) => false,
+    }
+  }
+}
+
+impl Generate for Spawner {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    match self {
+      Spawner::Ident   (ident) => quote! { let spawner = &mut #ident; },
+      Spawner::Field   (field) => quote! { let spawner = &mut #field; },
+      Spawner::Expr    (expr ) => quote! {
+        let mut spawner = (#expr);
+        let spawner = &mut spawner;
+      },
+      Spawner::Reborrow(expr ) => quote! {
+        let mut spawner = (#expr).reborrow();
+        let spawner = &mut spawner;
+      },
+      Spawner::Ref     (expr ) => quote! { let spawner = #expr; },
+
+      // `@` spawns with the commands of the `entity` in scope, e.g. in a code block extension
+      Spawner::Entity  (at   ) => quote_spanned! { at.span =>
+        let mut spawner = entity.commands();
+        let spawner = &mut spawner;
+      },
+    }
+  }
+}
+
+
+#[derive(Clone)]
+struct Definition {
+  spread    : Vec<Expr>,
+  components: proc_macro2::TokenStream,
+  deferred  : Vec<Expr>,
+  extensions: Vec<Extension>,
+  children  : Vec<Children>,
+  continued : Vec<Extension>,
+}
+
+impl Parse for Definition {
+  fn parse(input: ParseStream) -> Result<Self> {
+    if !input.peek(Paren) {
+      return Err(input.error("Expected '(' for definition"));
+    }
+
+    let (spread, components, deferred) = (|input: ParseStream| {
+      // `(..bundle, components)` spawns the bundle first, then inserts the rest on top of it, the
+      // following bundles of `(..a, ..b)` are inserted in order as well
+      let mut spread = vec![];
+
+      while input.peek(Token![..]) {
+        input.parse::<Token![..]>()?;
+        spread.push(input.parse()?);
+
+        if !input.is_empty() {
+          input.parse::<Token![,]>()?;
+        }
+      }
+
+      let components = input.parse::<proc_macro2::TokenStream>()?;
+      if !has_prefixed(&components) {
+        return Ok((spread, components, vec![]));
+      }
+
+      // `+component` is left out of the bundle, and inserted on its own after the entity is spawned
+      (|input: ParseStream| {
+        let mut initial  = quote! {};
+        let mut deferred = vec![];
+
+        while !input.is_empty() {
+          if input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            deferred.push(parse_component(input)?);
+          } else {
+            let component = parse_component(input)?;
+            initial.extend(quote! { #component, });
+          }
+
+          if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+          }
+        }
+
+        Ok((spread, initial, deferred))
+      }).parse2(components)
+    }).parse2(input.parse::<Group>()?.stream())?;
+
+    let extensions = {
+      let mut extensions = vec![];
+
+      while input.peek(Token![.]) {
+        if peek_children(input) {
+          break;
+        }
+
+        extensions.push(input.parse()?);
+      }
+
+      extensions
+    };
+
+    let children = {
+      let mut children = vec![];
+
+      while input.peek(Token![.]) && peek_children(input) {
+        input.parse::<Token![.]>()?;
+        children.push(input.parse()?);
+      }
+
+      children
+    };
+
+    // extensions after the children groups continue with the entity obtained again
+    let continued = {
+      let mut continued = vec![];
+      let mut first_dot = None;
+
+      while input.peek(Token![.]) {
+        // only the children groups split the definition, the extension in between is the culprit
+        if peek_children(input) {
+          let span = first_dot.unwrap_or_else(|| input.span());
+          return Err(Error::new(span, "method calls and observers must precede all children groups"));
+        }
+
+        first_dot.get_or_insert(input.span());
+        continued.push(input.parse()?);
+      }
+
+      continued
+    };
+
+    Ok(Definition {
+      spread,
+      components,
+      deferred,
+      extensions,
+      children,
+      continued,
+    })
+  }
+}
+
+impl Definition {
+  /// All the extensions, including the ones after the children groups.
+  fn all_extensions(&self) -> impl Iterator<Item = &Extension> {
+    self.extensions.iter().chain(&self.continued)
+  }
+
+  /// The extensions after the children groups, the spawner has been used by the groups, so the
+  /// entity has to be obtained again.
+  fn generate_continued(&self) -> proc_macro2::TokenStream {
+    if self.continued.is_empty() {
+      return quote! {};
+    }
+
+    let mut content = quote! {
+      let mut entity = ::bevy_toolbox::Spawnable::entity(spawner, this);
+    };
+
+    for ext in &self.continued {
+      content.extend(ext.generate());
+    }
+
+    content
+  }
+
+  /// Splits the components, along with the label, into the bundle to spawn with and the insertions
+  /// which follow it, the later is only needed with `..bundle` or `+component`.
+  fn split(&self, label: &Option<LitStr>) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let Definition { spread, components, deferred, .. } = self;
+
+    let label = label.as_ref().map(|l| quote! { Name::new(format!(#l)), });
+
+    let (initial, mut inserted) = match spread.as_slice() {
+      [] => (quote! { #label #components }, quote! {}),
+
+      [first, rest @ ..] => {
+        let mut inserted = quote! { #(entity.insert(#rest);)* };
+
+        if label.is_none() && components.is_empty() {
+          (quote! { #first }, inserted)
+        } else {
+          inserted.extend(quote! { entity.insert((#label #components)); });
+          (quote! { #first, }, inserted)
+        }
+      },
+    };
+
+    for component in deferred {
+      inserted.extend(quote! { entity.insert(#component); });
+    }
+
+    (initial, inserted)
+  }
+
+  /// Finds a `ChildOf(...)` written directly in the components.
+  fn find_child_of(&self) -> Option<Span> {
+    let tokens = self.components.clone().into_iter().collect::<Vec<_>>();
+
+    tokens.windows(2).find_map(|pair| match pair {
+      [proc_macro2::TokenTree::Ident(ident), proc_macro2::TokenTree::Group(group)]
+        if ident == "ChildOf" && group.delimiter() == proc_macro2::Delimiter::Parenthesis => Some(ident.span()),
+      _ => None,
+    })
+  }
+}
+
+
+#[derive(Clone)]
+struct Entity {
+  name      : Option<Ident>,
+  /// `#name`, the name is also inserted as the `Name` of the entity.
+  with_name : bool,
+  /// The name is forward declared with `pre`, so it's assigned instead of bound.
+  declared  : bool,
+  label     : Option<LitStr>,
+  definition: Definition,
+}
+
+impl Parse for Entity {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let (name, with_name) = if input.peek(Token![#]) && input.peek2(Ident) {
+      input.parse::<Token![#]>()?;
+      (Some(input.parse()?), true)
+    } else if input.peek(Ident) {
+      (Some(input.parse()?), false)
+    } else {
+      (None, false)
+    };
+
+    let label = if input.peek(Token![#]) {
+      if with_name {
+        return Err(input.error("The entity is already labeled by its name"));
+      }
+
+      input.parse::<Token![#]>()?;
+
+      if !input.peek(LitStr) {
+        return Err(input.error("Expected string literal for label"));
+      }
+
+      Some(input.parse()?)
+    } else {
+      None
+    };
+
+    Ok(Entity {
+      name,
+      with_name,
+      declared: false,
+      label,
+      definition: input.parse()?,
+    })
+  }
+}
+
+impl Generate for Entity {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Entity { definition, .. } = self;
+
+    let spawned  = self.spawned();
+    let captures = Extension::declare_captures(definition.all_extensions());
+    let naming   = self.naming();
+    quote! { #captures #naming #spawned; }
+  }
+}
+
+impl Entity {
+  /// The block spawning the entity and evaluating to its `Entity`, the captures aren't declared.
+  fn spawned(&self) -> proc_macro2::TokenStream {
+    let Entity     { definition, .. } = self;
+    let Definition { extensions, children, .. } = definition;
+    let (components, deferred) = definition.split(&self.label());
+
+    let spawn = if components.is_empty() {
+      quote! { ::bevy_toolbox::Spawnable::spawn_empty(spawner) }
+    } else {
+      quote! { ::bevy_toolbox::Spawnable::spawn(spawner, (#components)) }
+    };
+
+    let marker = self.marker();
+
+    let mut content = quote! {
+      #marker
+      let mut entity = #spawn;
+      #deferred
+
+      let this = entity.id();
+    };
+
+    for ext in extensions {
+      content.extend(ext.generate());
+    }
+
+    for group in children {
+      content.extend(group.generate());
+    }
+
+    content.extend(definition.generate_continued());
+
+    quote! { { #content this } }
+  }
+
+  /// The bundle of a plain entity for `for!`, i.e. one which is neither named nor extended, nor
+  /// refers to `this`. Anything else has to be spawned one by one.
+  fn batched(&self) -> Option<proc_macro2::TokenStream> {
+    let Entity     { name, definition, .. } = self;
+    let Definition { spread, deferred, extensions, children, continued, .. } = definition;
+
+    if name.is_some() || !spread.is_empty() || !deferred.is_empty() || !extensions.is_empty()
+    || !children.is_empty() || !continued.is_empty() || mentions(&definition.components, "this") {
+      return None;
+    }
+
+    let (components, _) = definition.split(&self.label());
+    Some(quote! { (#components) })
+  }
+
+  /// The binding of the name in front of the spawned entity, if any.
+  fn naming(&self) -> Option<proc_macro2::TokenStream> {
+    let name = self.name.as_ref()?;
+
+    if self.declared {
+      return Some(quote! { #name = });
+    }
+
+    // named only for readability is fine, the name doesn't have to be used, nor be unique
+    Some(quote! { #[allow(unused_variables, clippy::shadow_unrelated)] let #name = })
+  }
+
+  /// The label given by `#"..."`, or the name itself for `#name`.
+  fn label(&self) -> Option<LitStr> {
+    match &self.name {
+      Some(name) if self.with_name => Some(LitStr::new(&name.unraw().to_string(), name.span())),
+      _ => self.label.clone(),
+    }
+  }
+
+  /// With `entity-markers`, a no-op item naming the entity so expanded code maps back to source.
+  fn marker(&self) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "entity-markers") {
+      return quote! {};
+    }
+
+    let (text, span) = match (&self.name, &self.label) {
+      (Some(name), _) => (name.to_string(), name.span()),
+      (_, Some(label)) => (label.value()   , label.span()),
+      _ => ("<anonymous>".to_string(), Span::call_site()),
+    };
+
+    let text = LitStr::new(&format!("entity: {text}"), span);
+    quote_spanned! { span => const _: &str = #text; }
+  }
+}
+
+#[derive(Clone)]
+struct Parented {
+  parent: Ident,
+  /// `[expr] > ...`, the expression is evaluated once and bound to `parent`.
+  bound : Option<Expr>,
+  entity: Entity,
+}
+
+impl Parse for Parented {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let (parent, bound) = Parented::parse_parent(input)?;
+    input.parse::<Token![>]>()?;
+
+    Ok(Parented {
+      parent,
+      bound,
+      entity: input.parse()?,
+    })
+  }
+}
+
+impl Generate for Parented {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Parented { entity, .. } = self;
+    let Entity   { definition, .. } = entity;
+
+    let spawned  = self.spawned();
+    let captures = Extension::declare_captures(definition.all_extensions());
+    let naming   = entity.naming();
+    quote! { #captures #naming #spawned; }
+  }
+}
+
+impl Parented {
+  /// The parent is either a name or an expression in `[]`, like the spawner.
+  fn parse_parent(input: ParseStream) -> Result<(Ident, Option<Expr>)> {
+    if !input.peek(Bracket) {
+      return Ok((input.parse()?, None));
+    }
+
+    let content;
+    bracketed!(content in input);
+    Ok((Ident::new("parent", Span::mixed_site()), Some(content.parse()?)))
+  }
+
+  /// Same as [`Entity::spawned`], with the parent inserted.
+  fn spawned(&self) -> proc_macro2::TokenStream {
+    let Parented   { parent, bound, entity } = self;
+    let Entity     { definition, .. } = entity;
+    let Definition { extensions, children, .. } = definition;
+    let (components, deferred) = definition.split(&entity.label());
+
+    // the parent is already given, a second `ChildOf` would silently replace it
+    if let Some(span) = definition.find_child_of() {
+      return Error::new(span, "`ChildOf` conflicts with the parent given by the macro").to_compile_error();
+    }
+
+    let marker = entity.marker();
+    let bound  = bound.as_ref().map(|expr| quote! { let #parent: ::bevy_toolbox::bevy_ecs::entity::Entity = #expr; });
+
+    let mut content = quote! {
+      #marker
+      #bound
+      let mut entity = ::bevy_toolbox::Spawnable::spawn(spawner, (ChildOf(#parent), #components));
+      #deferred
+
+      let this = entity.id();
+    };
+
+    for ext in extensions {
+      content.extend(ext.generate());
+    }
+
+    for group in children {
+      content.extend(group.generate());
+    }
+
+    content.extend(definition.generate_continued());
+
+    quote! { { #content this } }
+  }
+}
+
+
+#[derive(Clone)]
+struct Reparented {
+  parent: Ident,
+  child : Ident,
+}
+
+impl Parse for Reparented {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let parent = input.parse()?;
+    input.parse::<Token![>]>()?;
+
+    Ok(Reparented {
+      parent,
+      child: input.parse()?,
+    })
+  }
+}
+
+impl Generate for Reparented {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Reparented { parent, child } = self;
+    quote! { ::bevy_toolbox::Spawnable::entity(spawner, #parent).add_child(#child); }
+  }
+}
+
+
+/// `base - (A, B)` removes the components of the given types from an existing entity.
+#[derive(Clone)]
+struct Removed {
+  base : Ident,
+  types: Vec<syn::Type>,
+}
+
+impl Parse for Removed {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let base = input.parse()?;
+    input.parse::<Token![-]>()?;
+
+    let content;
+    parenthesized!(content in input);
+
+    let types: Vec<syn::Type> = content
+      .parse_terminated(syn::Type::parse, Token![,])?
+      .into_iter().collect();
+
+    if types.is_empty() {
+      return Err(content.error("Expected at least one component type to remove"));
+    }
+
+    Ok(Removed { base, types })
+  }
+}
+
+impl Generate for Removed {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Removed { base, types } = self;
+    quote! { ::bevy_toolbox::Spawnable::entity(spawner, #base).remove::<(#(#types,)*)>(); }
+  }
+}
+
+
+/// `- base` despawns an existing entity along with its descendants, `-- base` is the same, as
+/// despawning is always recursive since Bevy 0.16.
+#[derive(Clone)]
+struct Despawned {
+  base: Ident,
+}
+
+impl Parse for Despawned {
+  fn parse(input: ParseStream) -> Result<Self> {
+    input.parse::<Token![-]>()?;
+
+    if input.peek(Token![-]) {
+      input.parse::<Token![-]>()?;
+    }
+
+    if !input.peek(Ident) {
+      return Err(input.error("Expected the name of the entity to despawn"));
+    }
+
+    Ok(Despawned { base: input.parse()? })
+  }
+}
+
+impl Generate for Despawned {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Despawned { base } = self;
+    quote! { ::bevy_toolbox::Spawnable::entity(spawner, #base).despawn(); }
+  }
+}
+
+
+#[derive(Clone)]
+struct Replaced {
+  entity: Entity,
+}
+
+impl Replaced {
+  /// `replace name ...` is told apart from an entity named `replace` by the name following it.
+  fn peek(input: ParseStream) -> bool {
+    input.cursor().ident().is_some_and(|(ident, _)| ident == "replace")
+  }
+
+  fn name(&self) -> &Ident {
+    self.entity.name.as_ref().expect("replaced entity is always named")
+  }
+}
+
+impl Parse for Replaced {
+  fn parse(input: ParseStream) -> Result<Self> {
+    input.parse::<Ident>()?;
+
+    Ok(Replaced {
+      entity: input.parse()?,
+    })
+  }
+}
+
+impl Generate for Replaced {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let name   = self.name();
+    let entity = self.entity.generate();
+
+    quote! {
+      ::bevy_toolbox::Spawnable::entity(spawner, #name).despawn();
+      #entity
+    }
+  }
+}
+
+
+#[derive(Clone)]
+struct Chosen {
+  name    : Ident,
+  branches: Vec<(proc_macro2::TokenStream, Entity)>,
+  fallback: Entity,
+  /// The name is forward declared with `pre`, so it's assigned instead of bound.
+  declared: bool,
+}
+
+impl Chosen {
+  /// `name if ...` names the entity spawned by whichever branch is taken.
+  fn peek(input: ParseStream) -> bool {
+    input.peek(Ident) && (
+      input.peek2(Token![if]) || input.peek2(Token![for]) || input.peek2(Token![while]) || input.peek2(Token![loop])
+    )
+  }
+
+  fn parse_branch(input: ParseStream) -> Result<Entity> {
+    let content;
+    braced!(content in input);
+
+    let entity = content.parse::<Entity>()?;
+    if let Some(name) = &entity.name {
+      return Err(Error::new(name.span(), "The entity in a named flow is named by the flow itself"));
+    }
+
+    while content.peek(Token![;]) {
+      content.parse::<Token![;]>()?;
+    }
+
+    if !content.is_empty() {
+      return Err(content.error("A branch of a named flow spawns exactly one entity"));
+    }
+
+    Ok(entity)
+  }
+
+  /// Generates the flow with `spawned` giving the block of each branch.
+  fn generate_with(&self, spawned: impl std::ops::Fn(&Entity) -> proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let Chosen { name, branches, fallback, declared: forwarded } = self;
+
+    // the same capture in several branches is declared once, so it's assigned by whichever is taken
+    let mut declared = vec![];
+    let entities = branches.iter().map(|(_, e)| e).chain([fallback]);
+    let captures = Extension::declare_captures(entities.flat_map(|e| e.definition.all_extensions()).filter(|ext| {
+      let Extension::Capture(name, _) = ext else { return false };
+      let fresh = !declared.contains(&name);
+      declared.push(name);
+      fresh
+    }));
+
+    let mut content = quote! {};
+    for (condition, entity) in branches {
+      let entity = spawned(entity);
+      content.extend(quote! { #condition #entity else });
+    }
+
+    let fallback = spawned(fallback);
+
+    // an attribute can't be put on the assignment itself
+    if *forwarded {
+      let chosen = Ident::new("chosen", Span::mixed_site());
+      return quote! {
+        #captures
+        #name = {
+          #[allow(irrefutable_let_patterns)]
+          let #chosen = #content #fallback;
+          #chosen
+        };
+      };
+    }
+
+    quote! {
+      #captures
+      #[allow(unused_variables, irrefutable_let_patterns, clippy::shadow_unrelated)]
+      let #name = #content #fallback;
+    }
+  }
+}
+
+impl Parse for Chosen {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let name = input.parse()?;
+
+    if input.peek(Token![for]) || input.peek(Token![while]) || input.peek(Token![loop]) {
+      return Err(input.error("A loop spawns any number of entities, only `if` can be named"));
+    }
+
+    let mut branches = vec![];
+
+    loop {
+      let if_ = input.parse::<Token![if]>()?;
+
+      let condition = if input.peek(Token![let]) {
+        let let_    = input.parse::<Token![let]>()?;
+        let pattern = Pat::parse_multi(input)?;
+        let eq      = input.parse::<Token![=]>()?;
+        let expr    = Expr::parse_without_eager_brace(input)?;
+        quote! { #if_ #let_ #pattern #eq #expr }
+      } else {
+        let expr = Expr::parse_without_eager_brace(input)?;
+        quote! { #if_ #expr }
+      };
+
+      branches.push((condition, Chosen::parse_branch(input)?));
+
+      if !input.peek(Token![else]) {
+        return Err(input.error("A named flow ends with `else`, so every path spawns the entity"));
+      }
+
+      input.parse::<Token![else]>()?;
+
+      if !input.peek(Token![if]) {
+        break;
+      }
+    }
+
+    Ok(Chosen {
+      name,
+      branches,
+      fallback: Chosen::parse_branch(input)?,
+      declared: false,
+    })
+  }
+}
+
+
+#[derive(Clone)]
+struct Inserted {
+  /// `name = base + ...` binds the entity inserted on to `name`.
+  name       : Option<Ident>,
+  base       : Ident,
+  definitions: Vec<Definition>,
+  /// The name is forward declared with `pre`, so it's assigned instead of bound.
+  declared   : bool,
+}
+
+impl Parse for Inserted {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let name = if input.peek2(Token![=]) {
+      let name = input.parse()?;
+      input.parse::<Token![=]>()?;
+      Some(name)
+    } else {
+      None
+    };
+
+    let base = input.parse()?;
+
+    let definitions = {
+      let mut definitions = vec![];
+
+      // at least one definition is required, the rest are chained with '+'
+      loop {
+        input.parse::<Token![+]>()?;
+        definitions.push(input.parse()?);
+
+        if !input.peek(Token![+]) {
+          break;
+        }
+      }
+
+      definitions
+    };
+
+    Ok(Inserted {
+      name,
+      base,
+      definitions,
+      declared: false,
+    })
+  }
+}
+
+impl Generate for Inserted {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Inserted { name, base, definitions, declared } = self;
+
+    let mut content = quote! {
+      let mut entity = ::bevy_toolbox::Spawnable::entity(spawner, #base);
+    };
+
+    for (i, definition) in definitions.iter().enumerate() {
+      let Definition { extensions, children, .. } = definition;
+      let (components, deferred) = definition.split(&None);
+
+      // children groups take the spawner, the entity has to be re-obtained for the next segment
+      if i > 0 && !definitions[i-1].children.is_empty() {
+        content.extend(quote! {
+          let mut entity = ::bevy_toolbox::Spawnable::entity(spawner, this);
+        });
+      }
+
+      content.extend(quote! {
+        entity.insert((#components));
+        #deferred
+
+        let this = entity.id();
+      });
+
+      for ext in extensions {
+        content.extend(ext.generate());
+      }
+
+      for group in children {
+        content.extend(group.generate());
+      }
+
+      content.extend(definition.generate_continued());
+    }
+
+    let captures = Extension::declare_captures(definitions.iter().flat_map(|d| d.all_extensions()));
+
+    if let Some(name) = name {
+      if *declared {
+        return quote! { #captures #name = { #content this }; };
+      }
+
+      return quote! { #captures #[allow(unused_variables, clippy::shadow_unrelated)] let #name = { #content this }; };
+    }
+
+    quote! { #captures { #content }; }
+  }
+}
+
+
+#[derive(Clone)]
+enum Child {
+  Entity   (Entity),
+  Inserted (Inserted),
+  Chosen   (Chosen),
+  Flow     (Flow<Child>),
+  CodeBlock(Group),
+  Let      (Stmt),
+  /// `...expr`, each bundle yielded by the iterator is spawned as a child.
+  Splat    (Expr),
+  /// `#[attr] child`, the attributes apply to every statement generated for the child.
+  Attributed(Vec<Attribute>, std::boxed::Box<Child>),
+  /// `pre a, b`, the names are declared up front and assigned by the entities named after them.
+  Pre      (Vec<Ident>),
+}
+
+impl Parse for Child {
+  fn parse(input: ParseStream) -> Result<Self> {
+    if input.peek(Token![#]) && input.peek2(Bracket) {
+      let attrs = input.call(Attribute::parse_outer)?;
+      return Ok(Child::Attributed(attrs, std::boxed::Box::new(input.parse()?)));
+    }
+
+    if input.peek(Paren    ) { return Child::parse_entity(input) }
+    if input.peek(Token![#]) { return Child::parse_entity(input) }
+    if input.peek(Brace    ) { return Ok(Child::CodeBlock(input.parse()?)) }
+    if input.peek(Token![let]) { return Ok(Child::Let      (parse_let(input)?)) }
+
+    if input.peek(Token![...]) {
+      input.parse::<Token![...]>()?;
+      return Ok(Child::Splat(input.parse()?));
+    }
+
+    if input.peek(Token![-]) {
+      return Err(input.error("Despawned is not allowed as a child"));
+    }
+
+    if input.peek(Token![if   ]) { return Ok(Child::Flow(input.parse()?)) }
+    if input.peek(Token![for  ]) { return Ok(Child::Flow(input.parse()?)) }
+    if input.peek(Token![while]) { return Ok(Child::Flow(input.parse()?)) }
+    if input.peek(Token![loop ]) { return Ok(Child::Flow(input.parse()?)) }
+    if input.peek(Token![match]) { return Ok(Child::Flow(input.parse()?)) }
+    if input.peek(Lifetime     ) { return Ok(Child::Flow(input.parse()?)) }
+
+    if input.peek(Token![break]) || input.peek(Token![continue]) {
+      return Err(control_outside_loop(input));
+    }
+
+    if input.peek(Ident) {
+      if input.peek2(Ident) && input.fork().parse::<Ident>().is_ok_and(|ident| ident == "pre") {
+        input.parse::<Ident>()?;
+
+        let mut names = vec![input.parse()?];
+        while input.peek(Token![,]) {
+          input.parse::<Token![,]>()?;
+          names.push(input.parse()?);
+        }
+
+        return Ok(Child::Pre(names));
+      }
+
+      if Chosen::peek(input)    { return Ok(Child::Chosen  (input.parse()?)) }
+      if input.peek2(Paren)     { return Child::parse_entity(input) }
+      if input.peek2(Token![#]) { return Child::parse_entity(input) }
+      if input.peek2(Token![+]) { return Ok(Child::Inserted(input.parse()?)) }
+      if input.peek2(Token![=]) { return Ok(Child::Inserted(input.parse()?)) }
+
+      input.parse::<Ident>()?;
+      return Err(input.error(
+        if input.peek(Token![>]) { "Parented is not allowed as a child" }
+        else if input.peek(Token![-]) { "Removed is not allowed as a child" }
+        else { "Expected '+' for inserted, or '()' for entity" }));
+    }
+
+    Err(input.error("Expected entity, inserted, flow statement or code block"))
+  }
+}
+
+impl Generate for Child {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    match self {
+      // the `;` keeps the block from ending up as the tail of a generated block, see `unused_braces`
+      Child::CodeBlock(block   ) => quote! { #block; },
+      Child::Let      (stmt    ) => quote! { #stmt },
+      Child::Inserted (inserted) => inserted.generate(),
+      Child::Chosen   (chosen  ) => chosen  .generate_with(|entity| {
+        let parent = Ident::new("parent", Span::call_site());
+        let entity = entity.clone();
+        Parented { parent, bound: None, entity }.spawned()
+      }),
+      Child::Flow     (Flow::For(for_)) if for_.batched => for_.generate_batched(Child::batched),
+      Child::Flow     (flow    ) => flow    .gen_irrefutable(),
+      Child::Splat    (iter    ) => Child::splat(iter, None),
+      Child::Attributed(attrs, child) => with_attributes(attrs, child.generate()),
+      Child::Pre      (names   ) => quote! { #(#[allow(unused_variables)] let #names;)* },
+      Child::Entity   (entity  ) => {
+        let parent = Ident::new("parent", Span::call_site());
+        let entity = entity.clone();
+        Parented { parent, bound: None, entity }.generate()
+      },
+    }
+  }
+}
+
+impl Child {
+  /// The parent and bundle of the child for `for!`, see [`Entity::batched`].
+  fn batched(&self) -> Option<(Option<Ident>, proc_macro2::TokenStream)> {
+    let Child::Entity(entity) = self else { return None };
+
+    // a `ChildOf` of its own is reported by the regular spawn
+    if entity.definition.find_child_of().is_some() {
+      return None;
+    }
+
+    Some((Some(Ident::new("parent", Span::call_site())), entity.batched()?))
+  }
+
+  /// Whether the generated code of the child needs the `parent` binding of its group.
+  fn uses_parent(&self) -> bool {
+    match self {
+      Child::CodeBlock (block     ) => mentions(&block.stream(), "parent"),
+      Child::Let       (stmt      ) => mentions(&stmt.to_token_stream(), "parent"),
+      Child::Pre       (_         ) => false,
+      Child::Attributed(_, child  ) => child.uses_parent(),
+      _                             => true,
+    }
+  }
+
+  /// Marks the entities named after the forward declared names, including the ones in flow bodies,
+  /// along with the names declared by a `pre` of the body itself.
+  fn forward(&mut self, names: &[Ident]) {
+    match self {
+      Child::Entity(entity) => {
+        entity.declared = entity.name.as_ref().is_some_and(|name| names.contains(name));
+      },
+      Child::Chosen(chosen) => {
+        chosen.declared = names.contains(&chosen.name);
+      },
+      Child::Inserted(inserted) => {
+        inserted.declared = inserted.name.as_ref().is_some_and(|name| names.contains(name));
+      },
+      Child::Flow(flow) => {
+        for body in flow.bodies_mut() {
+          // a `pre` in the body declares its names for the rest of the body
+          let names = names.iter().cloned().chain(body.iter().flat_map(|control| match control {
+            Control::Item(Child::Pre(names)) => names.clone(),
+            _                                => vec![],
+          })).collect::<Vec<_>>();
+
+          for control in body {
+            if let Control::Item(child) = control {
+              child.forward(&names);
+            }
+          }
+        }
+      },
+      Child::Attributed(_, child) => child.forward(names),
+      _ => {},
+    }
+  }
+
+  fn parse_entity(input: ParseStream) -> Result<Self> {
+    let entity = input.parse()?;
+
+    if input.peek(Token![<]) {
+      return Err(input.error("Parented is not allowed as a child"));
+    }
+
+    Ok(Child::Entity(entity))
+  }
+
+  /// Spawns each bundle of the iterator as a child, pushing the entities to `collected` if given.
+  fn splat(iter: &Expr, collected: Option<&Ident>) -> proc_macro2::TokenStream {
+    let bundle  = Ident::new("bundle", Span::mixed_site());
+    let spawned = quote! { ::bevy_toolbox::Spawnable::spawn(spawner, (ChildOf(parent), #bundle)) };
+
+    let spawned = match collected {
+      Some(collected) => quote! { #collected.push(#spawned.id()); },
+      None            => quote! { #spawned; },
+    };
+
+    quote! { for #bundle in #iter { #spawned } }
+  }
+}
+
+
+#[derive(Clone)]
+enum TopLevel {
+  Entity    (Entity),
+  Parented  (Parented),
+  Reparented(Reparented),
+  Removed   (Removed),
+  Despawned (Despawned),
+  Replaced  (Replaced),
+  Chosen    (Chosen),
+  Inserted  (Inserted),
+  Observer  (Observer),
+  Flow      (Flow<TopLevel>),
+  CodeBlock (Group),
+  Let       (Stmt),
+  /// `#[attr] top_level`, the attributes apply to every statement generated for the top level.
+  Attributed(Vec<Attribute>, std::boxed::Box<TopLevel>),
+}
+
+impl Parse for TopLevel {
+  fn parse(input: ParseStream) -> Result<Self> {
+    if input.peek(Token![#]) && input.peek2(Bracket) {
+      let attrs = input.call(Attribute::parse_outer)?;
+      return Ok(TopLevel::Attributed(attrs, std::boxed::Box::new(input.parse()?)));
+    }
+
+    if input.peek(Paren    ) { return TopLevel::parse_entity(input) }
+    if input.peek(Token![#]) { return TopLevel::parse_entity(input) }
+    if input.peek(Brace    ) { return Ok(TopLevel::CodeBlock(input.parse()?)) }
+    if input.peek(Bracket  ) { return Ok(TopLevel::Parented (input.parse()?)) }
+    if input.peek(Token![-]) { return Ok(TopLevel::Despawned(input.parse()?)) }
+    if input.peek(Token![let]) { return Ok(TopLevel::Let      (parse_let(input)?)) }
+
+    if input.peek(Token![...]) {
+      return Err(input.error("Splat is only allowed in a children group"));
+    }
+
+    if input.peek(Token![if   ]) { return Ok(TopLevel::Flow(input.parse()?)) }
+    if input.peek(Token![for  ]) { return Ok(TopLevel::Flow(input.parse()?)) }
+    if input.peek(Token![while]) { return Ok(TopLevel::Flow(input.parse()?)) }
+    if input.peek(Token![loop ]) { return Ok(TopLevel::Flow(input.parse()?)) }
+    if input.peek(Token![match]) { return Ok(TopLevel::Flow(input.parse()?)) }
+    if input.peek(Lifetime     ) { return Ok(TopLevel::Flow(input.parse()?)) }
+
+    if input.peek(Token![break]) || input.peek(Token![continue]) {
+      return Err(control_outside_loop(input));
+    }
+
+    if input.peek(Ident) {
+      if input.peek2(Ident) && Replaced::peek(input) {
+        return Ok(TopLevel::Replaced(input.parse()?));
+      }
+
+      if Chosen::peek(input) { return Ok(TopLevel::Chosen(input.parse()?)) }
+
+      if input.peek2(Paren) && Observer::peek(input) {
+        return Ok(TopLevel::Observer(input.parse()?));
+      }
+
+      if input.peek2(Paren) {
+        // `A(20);` reads as the entity `A` with the component `20`, which is most likely a tuple
+        // struct meant to be a bare component
+        let fork = input.fork();
+        let name = fork.parse::<Ident>()?;
+
+        if name.to_string().starts_with(char::is_uppercase) {
+          return Err(Error::new(name.span(), format!(
+            "`{name}(...)` would be an entity named `{name}`, wrap a tuple struct component in \
+            parentheses, like `({name}(...))`")));
+        }
+
+        return TopLevel::parse_entity(input);
+      }
+      if input.peek2(Token![#]) { return TopLevel::parse_entity(input) }
+      if input.peek2(Token![>]) {
+        // `parent > child;` moves an existing entity instead of spawning one
+        let fork = input.fork();
+        fork.parse::<Ident>()?;
+        fork.parse::<Token![>]>()?;
+
+        if fork.parse::<Ident>().is_ok() && (fork.is_empty() || fork.peek(Token![;])) {
+          return Ok(TopLevel::Reparented(input.parse()?));
+        }
+
+        return Ok(TopLevel::Parented(input.parse()?));
+      }
+      if input.peek2(Token![+]) { return Ok(TopLevel::Inserted(input.parse()?)) }
+      if input.peek2(Token![=]) { return Ok(TopLevel::Inserted(input.parse()?)) }
+      if input.peek2(Token![-]) { return Ok(TopLevel::Removed (input.parse()?)) }
+
+      // a bare component, like `Camera2d;`, `Node::default();` or `Node { ... };`
+      if input.peek2(Token![;]) || input.peek2(Token![::]) || input.peek2(Brace) || is_last_token(input) {
+        return TopLevel::parse_bare(input);
+      }
+
+      input.parse::<Ident>()?;
+      return Err(input.error("Expected '>' for parented, '+' for inserted, '-' for removed, or '()' for entity"));
+    }
+
+    Err(input.error("Expected parented, inserted, flow statement or code block"))
+  }
+}
+
+impl TopLevel {
+  /// Whether an entity is spawned, which can be returned from the macro.
+  fn is_spawned(&self) -> bool {
+    matches!(self, TopLevel::Entity(_) | TopLevel::Parented(_) | TopLevel::Replaced(_) | TopLevel::Chosen(_))
+  }
+
+  /// The name bound to the spawned entity, the unnamed one is given a hidden name so it can be returned.
+  fn returned_name(&mut self) -> Option<Ident> {
+    let root = || Ident::new("root", Span::mixed_site());
+
+    match self {
+      TopLevel::Entity  (entity  ) => Some(entity.name.get_or_insert_with(root).clone()),
+      TopLevel::Parented(parented) => Some(parented.entity.name.get_or_insert_with(root).clone()),
+      TopLevel::Replaced(replaced) => Some(replaced.entity.name.get_or_insert_with(root).clone()),
+      TopLevel::Chosen  (chosen  ) => Some(chosen.name.clone()),
+      _                            => None,
+    }
+  }
+
+  /// A single component spawned as is, the parentheses can only be left out when nothing follows it.
+  fn parse_bare(input: ParseStream) -> Result<Self> {
+    let mut tokens = vec![];
+    while !input.is_empty() && !input.peek(Token![;]) {
+      tokens.push(input.parse::<proc_macro2::TokenTree>()?);
+    }
+
+    // `.` followed by a group would be an extension or a children group, not a part of the component
+    let extended = tokens.windows(3).find_map(|window| match window {
+      [prev, proc_macro2::TokenTree::Punct(dot), proc_macro2::TokenTree::Group(_)]
+        if dot.as_char() == '.' && !matches!(prev, proc_macro2::TokenTree::Punct(p) if p.as_char() == '.')
+          => Some(dot.span()),
+      _ => None,
+    });
+
+    if let Some(span) = extended {
+      return Err(Error::new(span, "Wrap the component in '()' to extend it or give it children"));
+    }
+
+    let component = syn::parse2::<Expr>(tokens.into_iter().collect())?;
+
+    Ok(TopLevel::Entity(Entity {
+      name      : None,
+      with_name : false,
+      declared  : false,
+      label     : None,
+      definition: Definition {
+        spread    : vec![],
+        components: quote! { #component },
+        deferred  : vec![],
+        extensions: vec![],
+        children  : vec![],
+        continued : vec![],
+      },
+    }))
+  }
+
+  /// `entity < parent` is the same as `parent > entity`, the parent is only given after the entity.
+  fn parse_entity(input: ParseStream) -> Result<Self> {
+    let entity = input.parse()?;
+
+    if !input.peek(Token![<]) {
+      return Ok(TopLevel::Entity(entity));
+    }
+
+    input.parse::<Token![<]>()?;
+    let (parent, bound) = Parented::parse_parent(input)?;
+    Ok(TopLevel::Parented(Parented { parent, bound, entity }))
+  }
+}
+
+impl TopLevel {
+  /// The parent and bundle of the top level for `for!`, see [`Entity::batched`]. A parent given by
+  /// an expression is left to the regular spawn, so it's still evaluated once per entity.
+  fn batched(&self) -> Option<(Option<Ident>, proc_macro2::TokenStream)> {
+    match self {
+      TopLevel::Entity  (entity  ) => Some((None, entity.batched()?)),
+      TopLevel::Parented(parented) if parented.bound.is_none() && parented.entity.definition.find_child_of().is_none() => {
+        Some((Some(parented.parent.clone()), parented.entity.batched()?))
+      },
+      _ => None,
+    }
+  }
+}
+
+impl Generate for TopLevel {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    match self {
+      TopLevel::Entity    (entity    ) => entity    .generate(),
+      TopLevel::Parented  (parented  ) => parented  .generate(),
+      TopLevel::Reparented(reparented) => reparented.generate(),
+      TopLevel::Removed   (removed   ) => removed   .generate(),
+      TopLevel::Despawned (despawned ) => despawned .generate(),
+      TopLevel::Replaced  (replaced  ) => replaced  .generate(),
+      TopLevel::Chosen    (chosen    ) => chosen    .generate_with(Entity::spawned),
+      TopLevel::Inserted  (inserted  ) => inserted  .generate(),
+      TopLevel::Observer  (observer  ) => observer  .generate(),
+      TopLevel::Flow      (Flow::For(for_)) if for_.batched => for_.generate_batched(TopLevel::batched),
+      TopLevel::Flow      (flow      ) => flow      .gen_irrefutable(),
+      TopLevel::CodeBlock (block     ) => quote! { #block; },
+      TopLevel::Let       (stmt      ) => quote! { #stmt },
+      TopLevel::Attributed(attrs, top) => with_attributes(attrs, top.generate()),
+    }
+  }
+}
+
+
+#[derive(Clone)]
+struct Observer {
+  observer: Expr,
+}
+
+impl Observer {
+  /// `observe (Event) |trigger| ...` is told apart from an entity named `observe` by the closure.
+  fn peek(input: ParseStream) -> bool {
+    let fork = input.fork();
+
+    let Ok(ident) = fork.parse::<Ident>() else { return false };
+    if ident != "observe" || fork.parse::<Group>().is_err() {
+      return false;
+    }
+
+    fork.peek(Token![|]) || fork.peek(Token![||]) || fork.peek(Token![move])
+  }
+
+  /// The trigger is typed with the event, unless it's already typed.
+  fn type_trigger(observer: &mut ExprClosure, event: &syn::Type) {
+    if let Some(trigger) = observer.inputs.first_mut() {
+      if !matches!(trigger, Pat::Type(_)) {
+        *trigger = Pat::Type(PatType {
+          attrs      : vec![],
+          pat        : std::boxed::Box::new(trigger.clone()),
+          colon_token: <Token![:]>::default(),
+          ty         : parse_quote! { ::bevy_toolbox::bevy_ecs::observer::Trigger<#event> },
+        });
+      }
+    }
+  }
+
+  /// `(a, b)` after `.`, `.<Event>` or `.observe::<Event>`, the closures get their trigger typed when
+  /// the event is given.
+  fn parse_list(input: ParseStream, event: Option<&syn::Type>) -> Result<Vec<Expr>> {
+    let content;
+    parenthesized!(content in input);
+
+    // `.(a, b)` attaches each observer in order
+    let mut observers: Vec<Expr> = content
+      .parse_terminated(Expr::parse, Token![,])?
+      .into_iter().collect();
+
+    if observers.is_empty() {
+      return Err(content.error("Expected at least one observer"));
+    }
+
+    if let Some(event) = event {
+      for observer in &mut observers {
+        if let Expr::Closure(closure) = observer {
+          Observer::type_trigger(closure, event);
+        }
+      }
+    }
+
+    Ok(observers)
+  }
+}
+
+impl Parse for Observer {
+  fn parse(input: ParseStream) -> Result<Self> {
+    input.parse::<Ident>()?;
+
+    let event = {
+      let content;
+      parenthesized!(content in input);
+      content.parse::<syn::Type>()?
+    };
+
+    let mut observer = input.parse::<ExprClosure>()?;
+    Observer::type_trigger(&mut observer, &event);
+
+    Ok(Observer {
+      observer: Expr::Closure(observer),
+    })
+  }
+}
+
+impl Generate for Observer {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Observer { observer } = self;
+    quote! { ::bevy_toolbox::Spawnable::add_observer(spawner, #observer); }
+  }
+}
+
+
+/// Methods of `EntityCommands` which end with `_if` on their own, they are never treated as conditional.
+const NATIVE_IF_METHODS: &[&str] = &["insert_if", "try_insert_if"];
+
+/// Methods which can be made conditional with `_if`, any other method ending with `_if`, e.g. of an
+/// extension trait, is called as it is.
+const CONDITIONAL_METHODS: &[&str] = &[
+  // `EntityCommands`
+  "insert", "insert_if_new", "insert_recursive", "try_insert", "try_insert_if_new",
+  "remove", "remove_recursive", "remove_with_requires", "remove_by_id", "try_remove", "clear", "retain",
+  "despawn", "try_despawn", "observe", "trigger", "queue", "queue_handled", "log_components",
+  "add_child", "add_children", "insert_children", "remove_children", "replace_children", "with_child",
+  "with_children", "add_related", "add_one_related", "insert_related", "remove_related",
+  "replace_related", "despawn_related", "with_related", "with_related_entities",
+  "clone_with", "clone_components", "move_components",
+
+  // the extensions of the macro
+  "observe_global", "with", "apply", "this", "insert_opt", "child",
+];
+
+
+#[derive(Clone)]
+enum Extension {
+  /// The observers, along with the event when it's given by `.<Event>`, `.observe::<Event>` or
+  /// `.on::<Event>`.
+  Observe   (Option<syn::Type>, Vec<Expr>),
+  MethodCall(MethodCall),
+  /// The block, and whether `commands` is rebound to the entity's own in it, see
+  /// [`Extension::rebind_commands`].
+  CodeBlock (Group, bool),
+  Related   (syn::Type, Expr),
+  Global    (Expr),
+  With      (Expr),
+  Capture   (Ident, Expr),
+  Condition (Expr, std::boxed::Box<Extension>),
+  InsertIf  (Expr, proc_macro2::TokenStream),
+  /// `.insert_opt(option)`, the bundle is inserted only when the option is `Some`.
+  InsertOpt (Expr),
+
+  /// Unfinished is not a valid part of the grammar, it is used to allow the text editor correctly
+  /// shows the autocomplete suggestions. It always generates a compile error.
+  Unfinished(Token![.], Option<Ident>),
+}
+
+impl Parse for Extension {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let dot = input.parse::<Token![.]>()?;
+
+    // nothing follows the dot at all, it's a genuine mistake rather than a method name being typed
+    if input.is_empty() {
+      return Err(Error::new(dot.span, "expected method call, observe closure, code block, or children group"));
+    }
+
+    if input.peek(Ident) {
+      if input.peek2(Paren) {
+        let MethodCall(name, mut args) = input.parse()?;
+
+        // `method_if(cond, ...)` only calls `method(...)` when `cond` holds, except for the methods
+        // `EntityCommands` already provides with the same suffix
+        let text = name.to_string();
+        if let Some(method) = text.strip_suffix("_if") {
+          if !NATIVE_IF_METHODS.contains(&text.as_str()) && CONDITIONAL_METHODS.contains(&method) && !args.is_empty() {
+            let condition = args.remove(0);
+            let method    = Ident::new(method, name.span());
+            let extension = Extension::from_call(MethodCall(method, args))?;
+            return Ok(Extension::Condition(condition, std::boxed::Box::new(extension)));
+          }
+        }
+
+        return Extension::from_call(MethodCall(name, args));
+      }
+
+      if input.peek2(Token![=]) {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        if !input.peek(Paren) {
+          return Err(input.error("Expected '(' for captured observer"));
+        }
+
+        return Ok(Extension::Capture(name, {
+          let content;
+          parenthesized!(content in input);
+          content.parse()?
+        }));
+      }
+
+      if input.peek2(Token![::]) {
+        let name = input.parse::<Ident>()?;
+
+        // `on` is a shorter name for `observe` when the event is given
+        if name == "observe" || name == "on" {
+          input.parse::<Token![::]>()?;
+          input.parse::<Token![<]>()?;
+          let event = input.parse::<syn::Type>()?;
+          input.parse::<Token![>]>()?;
+
+          let observers = Observer::parse_list(input, Some(&event))?;
+          return Ok(Extension::Observe(Some(event), observers));
+        }
+
+        if name != "related" {
+          return Err(Error::new(name.span(), "Expected `related`, `observe` or `on` before `::`"));
+        }
+
+        input.parse::<Token![::]>()?;
+        input.parse::<Token![<]>()?;
+        let relationship = input.parse()?;
+        input.parse::<Token![>]>()?;
+
+        return Ok(Extension::Related(relationship, {
+          let content;
+          parenthesized!(content in input);
+          content.parse()?
+        }));
+      }
+
+      return Ok(Extension::Unfinished(dot, Some(input.parse()?)));
+    }
+
+    if input.peek(Paren) {
+      return Ok(Extension::Observe(None, Observer::parse_list(input, None)?));
+    }
+
+    // `.<Event>(...)` is the same as `.observe::<Event>(...)`
+    if input.peek(Token![<]) {
+      input.parse::<Token![<]>()?;
+      let event = input.parse::<syn::Type>()?;
+      input.parse::<Token![>]>()?;
+
+      let observers = Observer::parse_list(input, Some(&event))?;
+      return Ok(Extension::Observe(Some(event), observers));
+    }
+
+    if input.peek(Brace) {
+      return Ok(Extension::CodeBlock(input.parse()?, false));
+    }
+
+    if input.peek(Token![?]) {
+      input.parse::<Token![?]>()?;
+
+      let condition = {
+        let content;
+        parenthesized!(content in input);
+        content.parse()?
+      };
+
+      let content;
+      braced!(content in input);
+
+      if !content.peek(Paren) {
+        return Err(content.error("Expected '(' for conditionally inserted components"));
+      }
+
+      let components;
+      parenthesized!(components in content);
+
+      return Ok(Extension::InsertIf(condition, components.parse()?));
+    }
+
+    return Ok(Extension::Unfinished(dot, None));
+  }
+}
+
+impl Generate for Extension {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    match self {
+      Extension::Observe   (None, args) => quote! { #(entity.observe(#args);)* },
+      // the event is given to `observe` as well, so a generic observer gets it inferred from there
+      Extension::Observe   (Some(event), args) => quote! { #(entity.observe::<#event, _, _>(#args);)* },
+      Extension::MethodCall(method   ) => method.generate(),
+      Extension::CodeBlock (block, rebind) => {
+        let commands = rebind.then(|| quote! {
+          #[allow(unused_mut, clippy::shadow_unrelated)]
+          let mut commands = ::bevy_toolbox::HasCommands::commands(&mut entity);
+        });
+
+        quote! {{
+          let mut entity = ::bevy_toolbox::Reborrow::reborrow(&mut entity);
+          #commands
+          #block;
+        }}
+      },
+      Extension::Related   (ty, arg  ) => quote! {
+        entity.insert(<#ty as ::bevy_toolbox::bevy_ecs::relationship::Relationship>::from(#arg));
+      },
+      Extension::Global    (arg      ) => quote! { entity.commands().add_observer(#arg); },
+      Extension::With      (f        ) => {
+        // calling the closure directly leaves its parameter untyped, a generic call infers it
+        let with = Ident::new("with", Span::mixed_site());
+        quote! {{
+          fn #with<E>(entity: E, f: impl FnOnce(E)) { f(entity) }
+          #with(::bevy_toolbox::Reborrow::reborrow(&mut entity), #f);
+        }}
+      },
+      Extension::Capture   (name, arg) => quote! {
+        #name = entity.commands().spawn(::bevy_toolbox::bevy_ecs::observer::Observer::new(#arg).with_entity(this)).id();
+      },
+      Extension::Condition (cond, ext) => {
+        let ext = ext.generate();
+        quote! { if #cond { #ext } }
+      },
+      Extension::InsertIf  (cond, com) => quote! { if #cond { entity.insert((#com)); } },
+      Extension::InsertOpt (option   ) => {
+        let bundle = Ident::new("bundle", Span::mixed_site());
+        quote! { if let Some(#bundle) = (#option) { entity.insert(#bundle); } }
+      },
+      // the partial method is kept for the autocomplete, the error rejects it on final compile
+      Extension::Unfinished(dot, name) => {
+        if let Some(name) = name {
+          let error = Error::new(name.span(), format!("expected `(` after method name `{name}`"));
+          let error = error.to_compile_error();
+          quote! { entity #dot #name; #error }
+        } else {
+          let error = Error::new(dot.span, "expected method name, `(`, `{`, `[` or `?` after `.`");
+          let error = error.to_compile_error();
+          quote! { #error }
+        }
+      },
+    }
+  }
+}
+
+impl Extension {
+  /// Rebinds `commands` in a code block which uses it, for the spawners taking the user's `commands`.
+  /// Any other `commands`, e.g. next to a `World` spawner, is left as it is.
+  fn rebind_commands(&mut self) {
+    if let Extension::CodeBlock(block, rebind) = self {
+      *rebind = mentions(&block.stream(), "commands");
+    }
+  }
+
+  /// Observers captured with `.name = (...)` are declared in front of the entity, so they can still be
+  /// referenced after the entity is spawned.
+  fn declare_captures<'a>(extensions: impl IntoIterator<Item = &'a Extension>) -> proc_macro2::TokenStream {
+    let mut result = quote! {};
+
+    for ext in extensions {
+      if let Extension::Capture(name, _) = ext {
+        result.extend(quote! { #[allow(unused_variables, clippy::shadow_unrelated)] let #name; });
+      }
+    }
+
+    result
+  }
+
+  /// Turns `|this, params...| body` into a `move` closure over the remaining parameters, with the
+  /// first one bound to `this` up front.
+  fn bind_this(observer: Expr) -> Result<Expr> {
+    let Expr::Closure(mut closure) = observer else {
+      return Err(Error::new_spanned(observer, "Expected a closure taking the entity first for `this`"));
+    };
+
+    if closure.inputs.is_empty() {
+      return Err(Error::new_spanned(closure, "Expected the entity as the first parameter of the closure"));
+    }
+
+    let bound = closure.inputs.iter().next().cloned();
+    closure.inputs = closure.inputs.into_iter().skip(1).collect();
+    closure.capture = Some(<Token![move]>::default());
+
+    Ok(parse_quote! {{
+      let #bound = this;
+      #closure
+    }})
+  }
+
+  /// Method calls with special meaning are turned into their own extension.
+  fn from_call(call: MethodCall) -> Result<Self> {
+    let MethodCall(name, mut args) = call;
+
+    if name == "observe_global" {
+      if args.len() != 1 {
+        return Err(Error::new(name.span(), "Expected exactly 1 observer for `observe_global`"));
+      }
+
+      return Ok(Extension::Global(args.remove(0)));
+    }
+
+    // `apply` is another name for `with`
+    if name == "with" || name == "apply" {
+      if args.len() != 1 {
+        return Err(Error::new(name.span(), format!("Expected exactly 1 function for `{name}`")));
+      }
+
+      return Ok(Extension::With(args.remove(0)));
+    }
+
+    // `.this(|this, trigger| ...)` observes with the entity handed to the first parameter
+    if name == "this" {
+      if args.is_empty() {
+        return Err(Error::new(name.span(), "Expected at least one observer for `this`"));
+      }
+
+      let observers = args.into_iter().map(Extension::bind_this).collect::<Result<Vec<_>>>()?;
+      return Ok(Extension::Observe(None, observers));
+    }
+
+    if name == "insert_opt" {
+      if args.len() != 1 {
+        return Err(Error::new(name.span(), "Expected exactly 1 option for `insert_opt`"));
+      }
+
+      return Ok(Extension::InsertOpt(args.remove(0)));
+    }
+
+    // a lone child doesn't need a whole children group
+    if name == "child" {
+      if args.len() != 1 {
+        return Err(Error::new(name.span(), "Expected exactly 1 bundle for `child`"));
+      }
+
+      return Ok(Extension::MethodCall(MethodCall(Ident::new("with_child", name.span()), args)));
+    }
+
+    Ok(Extension::MethodCall(MethodCall(name, args)))
+  }
+}
+
+
+#[derive(Clone)]
+struct Children(Vec<Child>, Option<Expr>);
+
+impl Parse for Children {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let index = if input.peek(Ident) {
+      let name = input.parse::<Ident>()?;
+
+      if name != "children_at" {
+        return Err(Error::new(name.span(), "Expected `children_at` or '[' for children group"));
+      }
+
+      let content;
+      parenthesized!(content in input);
+      Some(content.parse()?)
+    } else {
+      None
+    };
+
+    Ok(Children({
+      let content;
+      bracketed!(content in input);
+
+      let mut children = vec![];
+      while !content.is_empty() {
+        if content.peek(Token![;]) {
+          content.parse::<Token![;]>()?;
+          continue;
+        }
+
+        children.push(content.parse()?);
+      }
+
+      children
+    }, index))
+  }
+}
+
+impl Generate for Children {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Children(children, index) = self;
+
+    if children.is_empty() {
+      return quote! {};
+    }
+
+    if let Some(bundle) = self.related_bundle() {
+      return quote! { entity.insert(#bundle); };
+    }
+
+    // the entities named after the forward declared names assign them instead of binding new ones
+    let names = children.iter().flat_map(|child| match child {
+      Child::Pre(names) => names.clone(),
+      _                 => vec![],
+    }).collect::<Vec<_>>();
+
+    let forwarded;
+    let children = if names.is_empty() && !children.iter().any(|child| matches!(child, Child::Flow(_) | Child::Attributed(..))) { children } else {
+      forwarded = children.iter().cloned().map(|mut child| { child.forward(&names); child }).collect::<Vec<_>>();
+      &forwarded
+    };
+
+    // a group of code blocks spawning nothing has no use for the parent, unless they refer to it
+    let mut result = if index.is_some() || children.iter().any(Child::uses_parent) {
+      quote! { let parent = this; }
+    } else {
+      quote! {}
+    };
+
+    let Some(index) = index else {
+      for child in children {
+        result.extend(child.generate());
+      }
+
+      return quote! { { #result }; };
+    };
+
+    // the direct entities are collected, so they can be placed at the index once all of them are spawned
+    // the helper names are fixed and hidden by the mixed site hygiene rather than numbered, so the same
+    // input always expands to the same code, which keeps the editor's inlay hints and diagnostics stable
+    let collected = Ident::new("children", Span::mixed_site());
+
+    result.extend(quote! {
+      let mut #collected = ::std::vec::Vec::<::bevy_toolbox::bevy_ecs::entity::Entity>::new();
+    });
+
+    for child in children {
+      if let Child::Chosen(chosen) = child {
+        let name = &chosen.name;
+        result.extend(child.generate());
+        result.extend(quote! { #collected.push(#name); });
+        continue;
+      }
+
+      if let Child::Splat(iter) = child {
+        result.extend(Child::splat(iter, Some(&collected)));
+        continue;
+      }
+
+      let Child::Entity(entity) = child else {
+        result.extend(child.generate());
+        continue;
+      };
+
+      let mut entity = entity.clone();
+      let parent = Ident::new("parent", Span::call_site());
+      let name   = entity.name.get_or_insert_with(|| Ident::new("child", Span::mixed_site())).clone();
+
+      result.extend(Parented { parent, bound: None, entity }.generate());
+      result.extend(quote! { #collected.push(#name); });
+    }
+
+    result.extend(quote! {
+      ::bevy_toolbox::Spawnable::entity(spawner, parent).insert_children(#index, &#collected);
+    });
+
+    quote! { { #result }; }
+  }
+}
+
+impl Children {
+  /// With `spawn-related`, a group of plain entities, which are neither named nor extended, is spawned
+  /// as a `Children::spawn` bundle inserted into the parent instead.
+  fn related_bundle(&self) -> Option<proc_macro2::TokenStream> {
+    let Children(children, index) = self;
+
+    if !cfg!(feature = "spawn-related") || index.is_some() || children.is_empty() {
+      return None;
+    }
+
+    let mut spawns = vec![];
+
+    for child in children {
+      let Child::Entity(Entity { name: None, label, definition, .. }) = child else { return None };
+      let Definition { spread, components, deferred, extensions, children, continued } = definition;
+
+      if !spread.is_empty() || !deferred.is_empty() || !extensions.is_empty() || !continued.is_empty() {
+        return None;
+      }
+
+      let mut bundle = vec![];
+      bundle.extend(label.as_ref().map(|l| quote! { Name::new(format!(#l)) }));
+      bundle.extend((!components.is_empty()).then(|| quote! { (#components) }));
+
+      for group in children {
+        bundle.push(group.related_bundle()?);
+      }
+
+      spawns.push(quote! { ::bevy_toolbox::bevy_ecs::spawn::Spawn((#(#bundle,)*)) });
+    }
+
+    Some(quote! {
+      <::bevy_toolbox::bevy_ecs::hierarchy::Children as ::bevy_toolbox::bevy_ecs::spawn::SpawnRelated>::spawn((#(#spawns,)*))
+    })
+  }
+}
+
+
+#[derive(Clone)]
+struct MethodCall(Ident, Vec<Expr>);
+
+impl Parse for MethodCall {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let name = input.parse()?;
+
+    let args = {
+      let content;
+      parenthesized!(content in input);
+
+      content
+        .parse_terminated(Expr::parse, Token![,])?
+        .into_iter().collect()
+    };
+
+    Ok(MethodCall(name, args))
+  }
+}
+
+impl Generate for MethodCall {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let MethodCall(name, args) = self;
+    quote! { entity. #name (#(#args),*); }
+  }
+}
+
+
+#[derive(Clone)]
+enum Flow<T: Generate+Parse> {
+  If      (If<T>),
+  IfLet   (IfLet<T>),
+  For     (For<T>),
+  While   (While<T>),
+  WhileLet(WhileLet<T>),
+  Loop    (Loop<T>),
+  Match   (Match<T>),
+}
+
+impl<T: Generate+Parse> Parse for Flow<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // the label is parsed by the loop itself, only what follows it decides the flow
+    if input.peek(Lifetime) {
+      let fork = input.fork();
+      fork.parse::<Label>()?;
+
+      if fork.peek(Token![for]) {
+        return Ok(Flow::For(input.parse()?));
+      }
+
+      if fork.peek(Token![while]) {
+        if fork.peek2(Token![let]) {
+          return Ok(Flow::WhileLet(input.parse()?));
+        } else {
+          return Ok(Flow::While(input.parse()?));
+        }
+      }
+
+      if fork.peek(Token![loop]) {
+        return Ok(Flow::Loop(input.parse()?));
+      }
+
+      return Err(fork.error("Only `for`, `while` and `loop` can be labeled"));
+    }
+
+    if input.peek(Token![if]) {
+      if input.peek2(Token![let]) {
+        return Ok(Flow::IfLet(input.parse()?));
+      } else {
+        return Ok(Flow::If(input.parse()?));
+      }
+    }
+
+    if input.peek(Token![for]) {
+      return Ok(Flow::For(input.parse()?));
+    }
+
+    if input.peek(Token![while]) {
+      if input.peek2(Token![let]) {
+        return Ok(Flow::WhileLet(input.parse()?));
+      } else {
+        return Ok(Flow::While(input.parse()?));
+      }
+    }
+
+    if input.peek(Token![loop]) {
+      return Ok(Flow::Loop(input.parse()?));
+    }
+
+    if input.peek(Token![match]) {
+      return Ok(Flow::Match(input.parse()?));
+    }
+
+    Err(input.error("Expected flow statement"))
+  }
+}
+
+impl<T: Generate+Parse> Generate for Flow<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    match self {
+      Flow::If      (if_      ) => if_      .generate(),
+      Flow::IfLet   (if_let   ) => if_let   .generate(),
+      Flow::For     (for_     ) => for_     .generate(),
+      Flow::While   (while_   ) => while_   .generate(),
+      Flow::WhileLet(while_let) => while_let.generate(),
+      Flow::Loop    (loop_    ) => loop_    .generate(),
+      Flow::Match   (match_   ) => match_   .generate(),
+    }
+  }
+}
+
+impl<T: Generate+Parse> Flow<T> {
+  /// Every body of the flow, including the ones in its `else` chain and the match arms.
+  fn bodies_mut(&mut self) -> Vec<&mut Vec<Control<T>>> {
+    let (body, else_) = match self {
+      Flow::If      (f) => (&mut f.body, f.else_.as_mut().map(|(_, e)| e)),
+      Flow::IfLet   (f) => (&mut f.body, f.else_.as_mut().map(|(_, e)| e)),
+      Flow::For     (f) => (&mut f.body, None),
+      Flow::While   (f) => (&mut f.body, None),
+      Flow::WhileLet(f) => (&mut f.body, None),
+      Flow::Loop    (f) => (&mut f.body, None),
+      Flow::Match   (f) => return f.arms.iter_mut().map(|arm| &mut arm.body).collect(),
+    };
+
+    let mut bodies = vec![body];
+
+    match else_ {
+      Some(ElseBranch::Flow (flow)) => bodies.extend(flow.bodies_mut()),
+      Some(ElseBranch::Block(body)) => bodies.push(body),
+      None => {},
+    }
+
+    bodies
+  }
+
+  /// Only the flows with a `let` pattern, including in their `else if` chain, need the allow,
+  /// `while let` already carries its own.
+  fn gen_irrefutable(&self) -> proc_macro2::TokenStream {
+    let content = self.generate();
+
+    if !self.has_if_let() {
+      return content;
+    }
+
+    quote! {
+      #[allow(irrefutable_let_patterns)]
+      #content
+    }
+  }
+
+  fn has_if_let(&self) -> bool {
+    match self {
+      Flow::IfLet(_) => true,
+      Flow::If(If { else_: Some((_, ElseBranch::Flow(flow))), .. }) => flow.has_if_let(),
+      _ => false,
+    }
+  }
+}
+
+
+#[derive(Clone)]
+struct If<T: Generate+Parse> {
+  if_      : syn::token::If,
+  condition: Expr,
+  body     : Vec<Control<T>>,
+  else_    : Option<(syn::token::Else, ElseBranch<T>)>,
+}
+
+impl<T: Generate+Parse> Parse for If<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let if_ = input.parse::<Token![if]>()?;
+
+    let condition = Expr::parse_without_eager_brace(input)?;
+    let body = {
+      let content;
+      braced!(content in input);
+
+      let mut body = vec![];
+      while !content.is_empty() {
+        if content.peek(Token![;]) {
+          content.parse::<Token![;]>()?;
+          continue;
+        }
+
+        body.push(content.parse()?);
+      }
+
+      body
+    };
+
+    let else_ = if input.peek(Token![else]) {
+      let else_ = input.parse::<Token![else]>()?;
+      Some((else_, input.parse()?))
+    } else {
+      None
+    };
+
+    Ok(If { if_, condition, body, else_ })
+  }
+}
+
+impl<T: Generate+Parse> Generate for If<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let If { if_, condition, body, else_ } = self;
+
+    let mut content = quote! {
+      #if_ #condition
+    };
+
+    let mut content_body = quote! {};
+    for item in body {
+      content_body.extend(item.generate());
+    }
+
+    content.extend(quote! {{ #content_body }});
+
+    if let Some((kw, else_)) = else_ {
+      let else_gen = else_.generate();
+      content.extend(quote! { #kw #else_gen });
+    }
+
+    content
+  }
+}
+
+
+#[derive(Clone)]
+enum ElseBranch<T: Generate+Parse> {
+  Flow (std::boxed::Box<Flow<T>>),
+  Block(Vec<Control<T>>),
+}
+
+impl<T: Generate+Parse> Parse for ElseBranch<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    if !input.peek(Brace) {
+      return Ok(ElseBranch::Flow(std::boxed::Box::new(input.parse()?)));
+    }
+
+    let content;
+    braced!(content in input);
+
+    let mut body = vec![];
+    while !content.is_empty() {
+      if content.peek(Token![;]) {
+        content.parse::<Token![;]>()?;
+        continue;
+      }
+
+      body.push(content.parse()?);
+    }
+
+    Ok(ElseBranch::Block(body))
+  }
+}
+
+impl<T: Generate+Parse> Generate for ElseBranch<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    match self {
+      ElseBranch::Flow(flow) => {
+        let content = flow.generate();
+
+        // `else if` chains are kept, the loops are wrapped to become the block of `else`
+        if matches!(**flow, Flow::If(_) | Flow::IfLet(_)) {
+          content
+        } else {
+          quote! {{ #content }}
+        }
+      }
+
+      ElseBranch::Block(body) => {
+        let mut content = quote! {};
+        for item in body {
+          content.extend(item.generate());
+        }
+
+        quote! {{ #content }}
+      }
+    }
+  }
+}
+
+
+#[derive(Clone)]
+struct IfLet<T: Generate+Parse> {
+  if_      : syn::token::If,
+  let_     : syn::token::Let,
+  pattern  : Pat,
+  condition: Expr,
+  body     : Vec<Control<T>>,
+  else_    : Option<(syn::token::Else, ElseBranch<T>)>,
+}
+
+impl<T: Generate+Parse> Parse for IfLet<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let if_  = input.parse::<Token![if]>()?;
+    let let_ = input.parse::<Token![let]>()?;
+
+    let pattern = Pat::parse_multi(input)?;
+    input.parse::<Token![=]>()?;
+    let condition = Expr::parse_without_eager_brace(input)?;
+
+    let body = {
+      let content;
+      braced!(content in input);
+
+      let mut body = vec![];
+      while !content.is_empty() {
+        if content.peek(Token![;]) {
+          content.parse::<Token![;]>()?;
+          continue;
+        }
+
+        body.push(content.parse()?);
+      }
+
+      body
+    };
+
+    let else_ = if input.peek(Token![else]) {
+      let else_ = input.parse::<Token![else]>()?;
+      Some((else_, input.parse()?))
+    } else {
+      None
+    };
+
+    Ok(IfLet { if_, let_, pattern, condition, body, else_ })
+  }
+}
+
+impl<T: Generate+Parse> Generate for IfLet<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let IfLet { if_, let_, pattern, condition, body, else_ } = self;
+
+    let mut content = quote! {
+      #if_ #let_ #pattern = #condition
+    };
+
+    let mut content_body = quote! {};
+    for item in body {
+      content_body.extend(item.generate());
+    }
+
+    content.extend(quote! {{ #content_body }});
+
+    if let Some((kw, else_)) = else_ {
+      let else_gen = else_.generate();
+      content.extend(quote! { #kw #else_gen });
+    }
+
+    content
+  }
+}
+
+
+#[derive(Clone)]
+struct For<T: Generate+Parse> {
+  label  : Option<Label>,
+  for_   : syn::token::For,
+  /// `for!`, the entities are spawned at once with `spawn_batch` when the body allows it.
+  batched: bool,
+  in_    : syn::token::In,
+  pattern: Pat,
+  iter   : Expr,
+  body   : Vec<Control<T>>,
+}
+
+impl<T: Generate+Parse> Parse for For<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let label = parse_label(input)?;
+    let for_  = input.parse::<Token![for]>()?;
+
+    let batched = input.peek(Token![!]);
+    if batched {
+      input.parse::<Token![!]>()?;
+    }
+
+    let pattern = Pat::parse_multi(input)?;
+    let in_     =input.parse::<Token![in]>()?;
+    let iter    = Expr::parse_without_eager_brace(input)?;
+
+    let body = {
+      let content;
+      braced!(content in input);
+
+      let mut body = vec![];
+      while !content.is_empty() {
+        if content.peek(Token![;]) {
+          content.parse::<Token![;]>()?;
+          continue;
+        }
+
+        body.push(content.parse()?);
+      }
+
+      body
+    };
+
+    Ok(For { label, for_, batched, in_, pattern, iter, body })
+  }
+}
+
+impl<T: Generate+Parse> Generate for For<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let For { label, for_, in_, pattern, iter, body, .. } = self;
+
+    let header = quote! {
+      #label #for_ #pattern #in_ #iter
+    };
+
+    let mut content_body = quote! {};
+    for item in body {
+      content_body.extend(item.generate());
+    }
+
+    quote! {#header { #content_body }}
+  }
+}
+
+impl<T: Generate+Parse> For<T> {
+  /// With `for!`, a body made of a single entity, whose parent and bundle are given by `bundle`, is
+  /// lowered to a `spawn_batch` over the mapped iterator. Any other body falls back to the regular loop.
+  fn generate_batched(
+    &self,
+    bundle: impl std::ops::Fn(&T) -> Option<(Option<Ident>, proc_macro2::TokenStream)>,
+  ) -> proc_macro2::TokenStream {
+    let For { label, pattern, iter, body, .. } = self;
+
+    let bundle = match body.as_slice() {
+      [Control::Item(item)] if label.is_none() => bundle(item),
+      _ => None,
+    };
+
+    let Some((parent, bundle)) = bundle else {
+      return self.generate();
+    };
+
+    // the batch is spawned without applying the commands queued by the hooks in between, so a parent
+    // without `Children` would get a new one for each child, replacing the previous one, it's given
+    // an empty one up front instead
+    let (prepared, bundle) = match parent {
+      Some(parent) => (
+        quote! { ::bevy_toolbox::Spawnable::entity(spawner, #parent).insert_if_new(::bevy_toolbox::bevy_ecs::hierarchy::Children::default()); },
+        quote! { (ChildOf(#parent), #bundle) },
+      ),
+      None => (quote! {}, bundle),
+    };
+
+    // an or-pattern needs the parentheses to be a closure parameter
+    let pattern = match pattern {
+      Pat::Or(_) => quote! { (#pattern) },
+      _          => quote! { #pattern },
+    };
+
+    quote! {
+      #prepared
+      ::bevy_toolbox::Spawnable::spawn_batch(
+        spawner,
+        ::std::iter::Iterator::map(::std::iter::IntoIterator::into_iter(#iter), move |#pattern| #bundle),
+      );
+    }
+  }
+}
+
+
+#[derive(Clone)]
+struct While<T: Generate+Parse> {
+  label    : Option<Label>,
+  while_   : syn::token::While,
+  condition: Expr,
+  body     : Vec<Control<T>>,
+}
+
+impl<T: Generate+Parse> Parse for While<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let label  = parse_label(input)?;
+    let while_ = input.parse::<Token![while]>()?;
+
+    let condition = Expr::parse_without_eager_brace(input)?;
+
+    let body = {
+      let content;
+      braced!(content in input);
+
+      let mut body = vec![];
+      while !content.is_empty() {
+        if content.peek(Token![;]) {
+          content.parse::<Token![;]>()?;
+          continue;
+        }
+
+        body.push(content.parse()?);
+      }
+
+      body
+    };
+
+    Ok(While { label, while_, condition, body })
+  }
+}
+
+impl<T: Generate+Parse> Generate for While<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let While { label, while_, condition, body } = self;
+
+    let header = quote! {
+      #label #while_ #condition
+    };
+
+    let mut content_body = quote! {};
+    for item in body {
+      content_body.extend(item.generate());
+    }
+
+    quote! {#header { #content_body }}
+  }
+}
+
+
+#[derive(Clone)]
+struct WhileLet<T: Generate+Parse> {
+  label    : Option<Label>,
+  while_   : syn::token::While,
+  let_     : syn::token::Let,
+  pattern  : Pat,
+  condition: Expr,
+  body     : Vec<Control<T>>,
+}
+
+impl<T: Generate+Parse> Parse for WhileLet<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let label  = parse_label(input)?;
+    let while_ = input.parse::<Token![while]>()?;
+    let let_   = input.parse::<Token![let]>()?;
+
+    let pattern = Pat::parse_multi(input)?;
+    input.parse::<Token![=]>()?;
+    let condition = Expr::parse_without_eager_brace(input)?;
+
+    let body = {
+      let content;
+      braced!(content in input);
+
+      let mut body = vec![];
+      while !content.is_empty() {
+        if content.peek(Token![;]) {
+          content.parse::<Token![;]>()?;
+          continue;
+        }
+
+        body.push(content.parse()?);
+      }
+
+      body
+    };
+
+    Ok(WhileLet { label, while_, let_, pattern, condition, body })
+  }
+}
+
+impl<T: Generate+Parse> Generate for WhileLet<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let WhileLet { label, while_, let_, pattern, condition, body } = self;
+
+    let header = quote! {
+      #[allow(irrefutable_let_patterns)]
+      #label #while_ #let_ #pattern = #condition
+    };
+
+    let mut content_body = quote! {};
+    for item in body {
+      content_body.extend(item.generate());
+    }
+
+    quote! {#header { #content_body }}
+  }
+}
+
+
+#[derive(Clone)]
+struct Loop<T: Generate+Parse> {
+  label: Option<Label>,
+  loop_: syn::token::Loop,
+  body : Vec<Control<T>>,
+}
+
+impl<T: Generate+Parse> Parse for Loop<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let label = parse_label(input)?;
+    let loop_ = input.parse::<Token![loop]>()?;
+
+    let body = {
+      let content;
+      braced!(content in input);
+
+      let mut body = vec![];
+      while !content.is_empty() {
+        if content.peek(Token![;]) {
+          content.parse::<Token![;]>()?;
+          continue;
+        }
+
+        body.push(content.parse()?);
+      }
+
+      body
+    };
+
+    Ok(Loop { label, loop_, body })
+  }
+}
+
+impl<T: Generate+Parse> Generate for Loop<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Loop { label, loop_, body } = self;
+
+    let mut content_body = quote! {};
+    for item in body {
+      content_body.extend(item.generate());
+    }
+
+    quote! {#label #loop_ { #content_body }}
+  }
+}
+
+
+#[derive(Clone)]
+struct Match<T: Generate+Parse> {
+  match_: syn::token::Match,
+  expr  : Expr,
+  arms  : Vec<MatchArm<T>>,
+}
+
+/// `PAT (if EXPR)? => { ... }`, the guard is kept as-is.
+#[derive(Clone)]
+struct MatchArm<T: Generate+Parse> {
+  pattern: Pat,
+  guard  : Option<(syn::token::If, Expr)>,
+  arrow  : syn::token::FatArrow,
+  body   : Vec<Control<T>>,
+}
+
+impl<T: Generate+Parse> Parse for Match<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let match_ = input.parse::<Token![match]>()?;
+    let expr   = Expr::parse_without_eager_brace(input)?;
+
+    let content;
+    braced!(content in input);
+
+    let mut arms = vec![];
+    while !content.is_empty() {
+      let pattern = Pat::parse_multi_with_leading_vert(&content)?;
+
+      let guard = if content.peek(Token![if]) {
+        Some((content.parse()?, content.parse()?))
+      } else {
+        None
+      };
+
+      let arrow = content.parse::<Token![=>]>()?;
+
+      if !content.peek(Brace) {
+        return Err(content.error("Expected '{' for the body of the arm"));
+      }
+
+      let body = {
+        let arm;
+        braced!(arm in content);
+
+        let mut body = vec![];
+        while !arm.is_empty() {
+          if arm.peek(Token![;]) {
+            arm.parse::<Token![;]>()?;
+            continue;
+          }
+
+          body.push(arm.parse()?);
+        }
+
+        body
+      };
+
+      arms.push(MatchArm { pattern, guard, arrow, body });
+
+      if content.peek(Token![,]) {
+        content.parse::<Token![,]>()?;
+      }
+    }
+
+    Ok(Match { match_, expr, arms })
+  }
+}
+
+impl<T: Generate+Parse> Generate for Match<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Match { match_, expr, arms } = self;
+
+    let arms = arms.iter().map(|MatchArm { pattern, guard, arrow, body }| {
+      let guard = guard.as_ref().map(|(if_, condition)| quote! { #if_ #condition });
+
+      let mut content_body = quote! {};
+      for item in body {
+        content_body.extend(item.generate());
+      }
+
+      quote! { #pattern #guard #arrow { #content_body } }
+    });
+
+    quote! {#match_ #expr { #(#arms)* }}
+  }
+}
+
+
+#[derive(Clone)]
+enum Control<T: Generate+Parse> {
+  Break(syn::token::Break, Option<Lifetime>),
+  Continue(syn::token::Continue, Option<Lifetime>),
+  Item(T),
+}
+
+impl<T: Generate+Parse> Parse for Control<T> {
+  fn parse(input: ParseStream) -> Result<Self> {
+    if input.peek(Token![break]) {
+      return Ok(Control::Break(input.parse()?, input.parse()?));
+    }
+
+    if input.peek(Token![continue]) {
+      return Ok(Control::Continue(input.parse()?, input.parse()?));
+    }
+
+    Ok(Control::Item(input.parse()?))
+  }
+}
+
+impl<T: Generate+Parse> Generate for Control<T> {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    match self {
+      Control::Break   (item, label) => quote! { #item #label; },
+      Control::Continue(item, label) => quote! { #item #label; },
+      Control::Item    (item       ) => item.generate(),
+    }
+  }
+}
+
+
+/// Rejects `break` and `continue` which aren't inside of a loop of the macro, no matter how deeply
+/// they are nested in the flows, children groups and branches, see [`control_outside_loop`].
+trait CheckControl {
+  /// `looped` is whether a loop of the macro is around.
+  fn check_control(&self, looped: bool) -> Result<()>;
+}
+
+impl CheckControl for TopLevel {
+  fn check_control(&self, looped: bool) -> Result<()> {
+    match self {
+      TopLevel::Entity    (entity  ) => entity.check_control(looped),
+      TopLevel::Parented  (parented) => parented.entity.check_control(looped),
+      TopLevel::Replaced  (replaced) => replaced.entity.check_control(looped),
+      TopLevel::Chosen    (chosen  ) => chosen.check_control(looped),
+      TopLevel::Inserted  (inserted) => inserted.check_control(looped),
+      TopLevel::Flow      (flow    ) => flow.check_control(looped),
+      TopLevel::Attributed(_, top  ) => top.check_control(looped),
+      _                              => Ok(()),
+    }
+  }
+}
+
+impl CheckControl for Child {
+  fn check_control(&self, looped: bool) -> Result<()> {
+    match self {
+      Child::Entity    (entity  ) => entity.check_control(looped),
+      Child::Inserted  (inserted) => inserted.check_control(looped),
+      Child::Chosen    (chosen  ) => chosen.check_control(looped),
+      Child::Flow      (flow    ) => flow.check_control(looped),
+      Child::Attributed(_, child) => child.check_control(looped),
+      _                           => Ok(()),
+    }
+  }
+}
+
+impl CheckControl for Entity {
+  fn check_control(&self, looped: bool) -> Result<()> {
+    self.definition.check_control(looped)
+  }
+}
+
+impl CheckControl for Chosen {
+  fn check_control(&self, looped: bool) -> Result<()> {
+    for (_, entity) in &self.branches {
+      entity.check_control(looped)?;
+    }
+
+    self.fallback.check_control(looped)
+  }
+}
+
+impl CheckControl for Inserted {
+  fn check_control(&self, looped: bool) -> Result<()> {
+    self.definitions.iter().try_for_each(|definition| definition.check_control(looped))
+  }
+}
+
+impl CheckControl for Definition {
+  fn check_control(&self, looped: bool) -> Result<()> {
+    for Children(children, _) in &self.children {
+      children.iter().try_for_each(|child| child.check_control(looped))?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<T: Generate+Parse+CheckControl> CheckControl for Flow<T> {
+  fn check_control(&self, looped: bool) -> Result<()> {
+    let (body, else_) = match self {
+      Flow::If      (f) => (&f.body, f.else_.as_ref().map(|(_, e)| e)),
+      Flow::IfLet   (f) => (&f.body, f.else_.as_ref().map(|(_, e)| e)),
+      Flow::For     (f) => return check_control_body(&f.body, true),
+      Flow::While   (f) => return check_control_body(&f.body, true),
+      Flow::WhileLet(f) => return check_control_body(&f.body, true),
+      Flow::Loop    (f) => return check_control_body(&f.body, true),
+      Flow::Match   (f) => return f.arms.iter().try_for_each(|arm| check_control_body(&arm.body, looped)),
+    };
+
+    check_control_body(body, looped)?;
+
+    match else_ {
+      Some(ElseBranch::Flow (flow)) => flow.check_control(looped),
+      Some(ElseBranch::Block(body)) => check_control_body(body, looped),
+      None                          => Ok(()),
+    }
+  }
+}
+
+fn check_control_body<T: Generate+Parse+CheckControl>(body: &[Control<T>], looped: bool) -> Result<()> {
+  for control in body {
+    match control {
+      Control::Break   (token, _) if !looped => return Err(Error::new(token.span, "`break` outside of a loop")),
+      Control::Continue(token, _) if !looped => return Err(Error::new(token.span, "`continue` outside of a loop")),
+      Control::Item    (item    )            => item.check_control(looped)?,
+      _                                      => {},
+    }
+  }
+
+  Ok(())
+}
+
+
+/// Visits every extension of the macro, including the ones of children, branches and flow bodies.
+trait VisitExtensions {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension));
+}
+
+impl VisitExtensions for TopLevel {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension)) {
+    match self {
+      TopLevel::Entity    (entity  ) => entity.visit_extensions(f),
+      TopLevel::Parented  (parented) => parented.entity.visit_extensions(f),
+      TopLevel::Replaced  (replaced) => replaced.entity.visit_extensions(f),
+      TopLevel::Chosen    (chosen  ) => chosen.visit_extensions(f),
+      TopLevel::Inserted  (inserted) => inserted.visit_extensions(f),
+      TopLevel::Flow      (flow    ) => flow.visit_extensions(f),
+      TopLevel::Attributed(_, top  ) => top.visit_extensions(f),
+      _                              => {},
+    }
+  }
+}
+
+impl VisitExtensions for Child {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension)) {
+    match self {
+      Child::Entity    (entity  ) => entity.visit_extensions(f),
+      Child::Inserted  (inserted) => inserted.visit_extensions(f),
+      Child::Chosen    (chosen  ) => chosen.visit_extensions(f),
+      Child::Flow      (flow    ) => flow.visit_extensions(f),
+      Child::Attributed(_, child) => child.visit_extensions(f),
+      _                           => {},
+    }
+  }
+}
+
+impl VisitExtensions for Entity {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension)) {
+    self.definition.visit_extensions(f);
+  }
+}
+
+impl VisitExtensions for Chosen {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension)) {
+    for (_, entity) in &mut self.branches {
+      entity.visit_extensions(f);
+    }
+
+    self.fallback.visit_extensions(f);
+  }
+}
+
+impl VisitExtensions for Inserted {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension)) {
+    for definition in &mut self.definitions {
+      definition.visit_extensions(f);
+    }
+  }
+}
+
+impl VisitExtensions for Definition {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension)) {
+    for ext in self.extensions.iter_mut().chain(&mut self.continued) {
+      ext.visit_extensions(f);
+    }
+
+    for Children(children, _) in &mut self.children {
+      children.iter_mut().for_each(|child| child.visit_extensions(f));
+    }
+  }
+}
+
+impl VisitExtensions for Extension {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension)) {
+    f(self);
+
+    if let Extension::Condition(_, ext) = self {
+      ext.visit_extensions(f);
+    }
+  }
+}
+
+impl<T: Generate+Parse+VisitExtensions> VisitExtensions for Flow<T> {
+  fn visit_extensions(&mut self, f: &mut impl FnMut(&mut Extension)) {
+    for body in self.bodies_mut() {
+      for control in body {
+        if let Control::Item(item) = control {
+          item.visit_extensions(f);
+        }
+      }
+    }
+  }
+}
+
+
+/// Scopes of the names declared in the macro. Every children group and flow body is its own scope,
+/// a name is only visible in the scope it's declared in and the scopes nested inside of it.
+#[derive(Default)]
+struct Scope {
+  declared: std::collections::HashMap<String, Vec<usize>>,
+  stack   : Vec<usize>,
+  next    : usize,
+  /// The names bound by the patterns of the flows around, e.g. `for item in ...`.
+  bound   : Vec<String>,
+  errors  : proc_macro2::TokenStream,
+}
+
+impl Scope {
+  /// Checks the whole macro, the names are collected first, so a name can be found no matter where
+  /// it's declared.
+  ///
+  /// The names bound inside of the tokens, e.g. closure parameters, are never reported, since the
+  /// reference is to the local then.
+  fn check(top_level: &[TopLevel]) -> proc_macro2::TokenStream {
+    let mut collect = Scope::default();
+    top_level.iter().for_each(|item| item.check(&mut collect));
+
+    let mut scope = Scope { declared: collect.declared, ..Scope::default() };
+    top_level.iter().for_each(|item| item.check(&mut scope));
+
+    scope.errors
+  }
+
+  fn nested(&mut self, f: impl FnOnce(&mut Self)) {
+    self.next += 1;
+    self.stack.push(self.next);
+    f(self);
+    self.stack.pop();
+  }
+
+  fn declare(&mut self, name: &Ident) {
+    let id = self.stack.last().copied().unwrap_or(0);
+    self.declared.entry(name.to_string()).or_default().push(id);
+  }
+
+  /// Binds the names of a flow pattern while checking its body.
+  fn bind(&mut self, pattern: &Pat, f: impl FnOnce(&mut Self)) {
+    let len = self.bound.len();
+    pattern_bindings(pattern, &mut self.bound);
+    f(self);
+    self.bound.truncate(len);
+  }
+
+  /// Reports the name when it's only declared in scopes which aren't visible from here.
+  fn refer(&mut self, name: &Ident) {
+    let Some(ids) = self.declared.get(&name.to_string()) else { return };
+
+    if ids.iter().any(|id| *id == 0 || self.stack.contains(id)) || self.bound.contains(&name.to_string()) {
+      return;
+    }
+
+    self.errors.extend(Error::new(name.span(), format!(
+      "`{name}` is declared in another children group or flow body, names in the macro are only \
+      visible within the group they are declared in, rename the entity if a binding from outside of \
+      the macro is meant")).to_compile_error());
+  }
+
+  /// Refers every name the tokens might use as a value, including the ones in format strings.
+  fn refer_tokens(&mut self, tokens: proc_macro2::TokenStream) {
+    let mut locals = std::collections::HashSet::new();
+    bound_names(&tokens, &mut locals);
+    self.refer_tokens_in(tokens, &locals);
+  }
+
+  /// `locals` are the names bound anywhere inside the tokens, see [`bound_names`].
+  fn refer_tokens_in(&mut self, tokens: proc_macro2::TokenStream, locals: &std::collections::HashSet<String>) {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+
+    for (i, token) in tokens.iter().enumerate() {
+      let prev = i.checked_sub(1).and_then(|i| tokens.get(i));
+      let next = tokens.get(i + 1);
+
+      let is_punct = |token: Option<&proc_macro2::TokenTree>, chars: &str|
+        matches!(token, Some(proc_macro2::TokenTree::Punct(p)) if chars.contains(p.as_char()));
+      let is_call = matches!(next, Some(proc_macro2::TokenTree::Group(g)) if g.delimiter() == proc_macro2::Delimiter::Parenthesis);
+
+      match token {
+        proc_macro2::TokenTree::Group  (group) => self.refer_tokens_in(group.stream(), locals),
+        proc_macro2::TokenTree::Ident  (ident) => {
+          // fields, methods, paths, macros and calls are not values
+          if !is_punct(prev, ".:") && !is_punct(next, ":!") && !is_call && !locals.contains(&ident.to_string()) {
+            self.refer(ident);
+          }
+        },
+        proc_macro2::TokenTree::Literal(lit  ) => {
+          let Ok(lit) = syn::parse2::<LitStr>(lit.to_token_stream()) else { continue };
+
+          let value  = lit.value();
+          let pieces = value.split('{').collect::<Vec<_>>();
+
+          // a piece following an empty one is escaped with `{{`
+          for (i, arg) in pieces.iter().enumerate().skip(1) {
+            if i > 1 && pieces[i - 1].is_empty() {
+              continue;
+            }
+
+            let name = arg.split([':', '}']).next().unwrap_or_default();
+
+            if syn::parse_str::<Ident>(name).is_ok() && !locals.contains(name) {
+              self.refer(&Ident::new(name, lit.span()));
+            }
+          }
+        },
+        proc_macro2::TokenTree::Punct  (_    ) => {},
+      }
+    }
+  }
+}
+
+trait Check {
+  fn check(&self, scope: &mut Scope);
+}
+
+impl Check for TopLevel {
+  fn check(&self, scope: &mut Scope) {
+    match self {
+      TopLevel::Entity    (entity    ) => entity.check(scope),
+      TopLevel::Parented  (parented  ) => parented.check(scope),
+      TopLevel::Reparented(reparented) => {
+        scope.refer(&reparented.parent);
+        scope.refer(&reparented.child );
+      },
+      TopLevel::Removed   (removed   ) => scope.refer(&removed.base),
+      TopLevel::Despawned (despawned ) => scope.refer(&despawned.base),
+      TopLevel::Replaced  (replaced  ) => {
+        scope.refer(replaced.name());
+        replaced.entity.check(scope);
+      },
+      TopLevel::Chosen    (chosen    ) => chosen.check(scope),
+      TopLevel::Inserted  (inserted  ) => inserted.check(scope),
+      TopLevel::Observer  (observer  ) => scope.refer_tokens(observer.observer.to_token_stream()),
+      TopLevel::Flow      (flow      ) => flow.check(scope),
+      TopLevel::CodeBlock (block     ) => scope.refer_tokens(block.stream()),
+      TopLevel::Let       (stmt      ) => check_let(stmt, scope),
+      TopLevel::Attributed(_, top    ) => top.check(scope),
+    }
+  }
+}
+
+impl Check for Child {
+  fn check(&self, scope: &mut Scope) {
+    match self {
+      Child::Entity   (entity  ) => entity.check(scope),
+      Child::Inserted (inserted) => inserted.check(scope),
+      Child::Chosen   (chosen  ) => chosen.check(scope),
+      Child::Flow     (flow    ) => flow.check(scope),
+      Child::CodeBlock(block   ) => scope.refer_tokens(block.stream()),
+      Child::Let      (stmt    ) => check_let(stmt, scope),
+      Child::Splat    (iter    ) => scope.refer_tokens(iter.to_token_stream()),
+      Child::Attributed(_, child ) => child.check(scope),
+      Child::Pre      (names   ) => names.iter().for_each(|name| scope.declare(name)),
+    }
+  }
+}
+
+impl Check for Entity {
+  fn check(&self, scope: &mut Scope) {
+    // captures are declared in front of the entity, the name is only assigned after it
+    self.definition.declare_captures(scope);
+    self.definition.check(scope);
+
+    if let Some(name) = &self.name {
+      scope.declare(name);
+    }
+  }
+}
+
+impl Check for Chosen {
+  fn check(&self, scope: &mut Scope) {
+    for (condition, entity) in &self.branches {
+      scope.refer_tokens(condition.clone());
+      entity.definition.declare_captures(scope);
+      scope.nested(|scope| entity.definition.check(scope));
+    }
+
+    self.fallback.definition.declare_captures(scope);
+    scope.nested(|scope| self.fallback.definition.check(scope));
+    scope.declare(&self.name);
+  }
+}
+
+impl Check for Parented {
+  fn check(&self, scope: &mut Scope) {
+    match &self.bound {
+      Some(expr) => scope.refer_tokens(expr.to_token_stream()),
+      None       => scope.refer(&self.parent),
+    }
+
+    self.entity.check(scope);
+  }
+}
+
+impl Check for Inserted {
+  fn check(&self, scope: &mut Scope) {
+    scope.refer(&self.base);
+
+    for definition in &self.definitions {
+      definition.declare_captures(scope);
+    }
+
+    for definition in &self.definitions {
+      definition.check(scope);
+    }
+
+    if let Some(name) = &self.name {
+      scope.declare(name);
+    }
+  }
+}
+
+impl Definition {
+  fn declare_captures(&self, scope: &mut Scope) {
+    for ext in self.all_extensions() {
+      if let Extension::Capture(name, _) = ext {
+        scope.declare(name);
+      }
+    }
+  }
+}
+
+impl Check for Definition {
+  fn check(&self, scope: &mut Scope) {
+    let Definition { spread, components, deferred, extensions, children, continued } = self;
+
+    scope.refer_tokens(quote! { #(#spread)* #components #(#deferred)* });
+
+    for ext in extensions {
+      ext.check(scope);
+    }
+
+    for Children(children, index) in children {
+      scope.refer_tokens(index.to_token_stream());
+      scope.nested(|scope| children.iter().for_each(|child| child.check(scope)));
+    }
+
+    for ext in continued {
+      ext.check(scope);
+    }
+  }
+}
+
+impl Check for Extension {
+  fn check(&self, scope: &mut Scope) {
+    match self {
+      Extension::Observe   (_, args  ) => scope.refer_tokens(quote! { #(#args)* }),
+      Extension::MethodCall(method   ) => {
+        let MethodCall(_, args) = method;
+        scope.refer_tokens(quote! { #(#args)* });
+      },
+      Extension::CodeBlock (block, _ ) => scope.refer_tokens(block.stream()),
+      Extension::Related   (_, arg   ) => scope.refer_tokens(arg.to_token_stream()),
+      Extension::Global    (arg      ) => scope.refer_tokens(arg.to_token_stream()),
+      Extension::With      (f        ) => scope.refer_tokens(f.to_token_stream()),
+      Extension::Capture   (_, arg   ) => scope.refer_tokens(arg.to_token_stream()),
+      Extension::Condition (cond, ext) => {
+        scope.refer_tokens(cond.to_token_stream());
+        ext.check(scope);
+      },
+      Extension::InsertIf  (cond, com) => scope.refer_tokens(quote! { #cond #com }),
+      Extension::InsertOpt (option   ) => scope.refer_tokens(option.to_token_stream()),
+      Extension::Unfinished(..       ) => {},
+    }
+  }
+}
+
+impl<T: Generate+Parse+Check> Check for Flow<T> {
+  fn check(&self, scope: &mut Scope) {
+    let (condition, pattern, body, else_) = match self {
+      Flow::If      (f) => (Some(&f.condition), None            , &f.body, f.else_.as_ref().map(|(_, e)| e)),
+      Flow::IfLet   (f) => (Some(&f.condition), Some(&f.pattern), &f.body, f.else_.as_ref().map(|(_, e)| e)),
+      Flow::For     (f) => (Some(&f.iter     ), Some(&f.pattern), &f.body, None),
+      Flow::While   (f) => (Some(&f.condition), None            , &f.body, None),
+      Flow::WhileLet(f) => (Some(&f.condition), Some(&f.pattern), &f.body, None),
+      Flow::Loop    (f) => (None              , None            , &f.body, None),
+
+      // every arm is a body of its own
+      Flow::Match   (f) => {
+        scope.refer_tokens(f.expr.to_token_stream());
+
+        for arm in &f.arms {
+          scope.bind(&arm.pattern, |scope| {
+            if let Some((_, guard)) = &arm.guard {
+              scope.refer_tokens(guard.to_token_stream());
+            }
+
+            check_body(&arm.body, scope);
+          });
+        }
+
+        return;
+      },
+    };
+
+    if let Some(condition) = condition {
+      scope.refer_tokens(condition.to_token_stream());
+    }
+
+    match pattern {
+      Some(pattern) => scope.bind(pattern, |scope| check_body(body, scope)),
+      None          => check_body(body, scope),
+    }
+
+    match else_ {
+      Some(ElseBranch::Flow (flow)) => flow.check(scope),
+      Some(ElseBranch::Block(body)) => check_body(body, scope),
+      None => {},
+    }
+  }
+}
+
+fn check_body<T: Generate+Parse+Check>(body: &[Control<T>], scope: &mut Scope) {
+  scope.nested(|scope| {
+    for control in body {
+      if let Control::Item(item) = control {
+        item.check(scope);
+      }
+    }
+  });
+}
+
+fn check_let(stmt: &Stmt, scope: &mut Scope) {
+  let Stmt::Local(local) = stmt else { return };
+
+  if let Some(init) = &local.init {
+    scope.refer_tokens(init.expr.to_token_stream());
+  }
+
+  if let Pat::Ident(pat) = &local.pat {
+    scope.declare(&pat.ident);
+  }
+}
+
+
+/// The names bound by a pattern, e.g. `item` and `index` of `(index, Some(item))`.
+fn pattern_bindings(pattern: &Pat, names: &mut Vec<String>) {
+  match pattern {
+    Pat::Ident      (pat) => {
+      names.push(pat.ident.to_string());
+
+      if let Some((_, sub)) = &pat.subpat {
+        pattern_bindings(sub, names);
+      }
+    },
+    Pat::Tuple      (pat) => pat.elems.iter().for_each(|pat| pattern_bindings(pat, names)),
+    Pat::TupleStruct(pat) => pat.elems.iter().for_each(|pat| pattern_bindings(pat, names)),
+    Pat::Slice      (pat) => pat.elems.iter().for_each(|pat| pattern_bindings(pat, names)),
+    Pat::Or         (pat) => pat.cases.iter().for_each(|pat| pattern_bindings(pat, names)),
+    Pat::Struct     (pat) => pat.fields.iter().for_each(|field| pattern_bindings(&field.pat, names)),
+    Pat::Reference  (pat) => pattern_bindings(&pat.pat, names),
+    Pat::Paren      (pat) => pattern_bindings(&pat.pat, names),
+    Pat::Type       (pat) => pattern_bindings(&pat.pat, names),
+    _ => {},
+  }
+}
+
+/// The names bound inside of the tokens, by `let` and `for` patterns, match arms, closure parameters,
+/// nested functions and named format arguments. Where in the tokens they're bound isn't tracked, a
+/// name bound anywhere is taken as a local everywhere, which only leaves out findings.
+fn bound_names(tokens: &proc_macro2::TokenStream, names: &mut std::collections::HashSet<String>) {
+  fn collect(tokens: &[proc_macro2::TokenTree], names: &mut std::collections::HashSet<String>) {
+    for token in tokens {
+      match token {
+        proc_macro2::TokenTree::Ident(ident) => { names.insert(ident.to_string()); },
+        proc_macro2::TokenTree::Group(group) => collect(&group.stream().into_iter().collect::<Vec<_>>(), names),
+        _                                    => {},
+      }
+    }
+  }
+
+  let tokens  = tokens.clone().into_iter().collect::<Vec<_>>();
+  let punct   = |i: usize, c: char| matches!(tokens.get(i), Some(proc_macro2::TokenTree::Punct(p)) if p.as_char() == c);
+  let ident   = |i: usize, s: &str| matches!(tokens.get(i), Some(proc_macro2::TokenTree::Ident(p)) if p == s);
+  let find    = |from: usize, stop: &dyn std::ops::Fn(usize) -> bool| (from..tokens.len()).find(|&i| stop(i)).unwrap_or(tokens.len());
+  let mut arm = 0;
+
+  for (i, token) in tokens.iter().enumerate() {
+    match token {
+      proc_macro2::TokenTree::Group(group) => {
+        bound_names(&group.stream(), names);
+
+        // `name = value` in a macro call, e.g. `format!("{name}", name = value)`
+        if i > 0 && punct(i - 1, '!') {
+          let inner = group.stream().into_iter().collect::<Vec<_>>();
+
+          for pair in inner.windows(3) {
+            if let [proc_macro2::TokenTree::Ident(name), proc_macro2::TokenTree::Punct(eq), next] = pair {
+              let lone = !matches!(next, proc_macro2::TokenTree::Punct(p) if "=>".contains(p.as_char()));
+              if eq.as_char() == '=' && lone {
+                names.insert(name.to_string());
+              }
+            }
+          }
+        }
+
+        // a block ends an arm of a `match` without a `,`
+        if group.delimiter() == proc_macro2::Delimiter::Brace {
+          arm = i + 1;
+        }
+      },
+      proc_macro2::TokenTree::Ident(_) if ident(i, "let") => {
+        collect(&tokens[i + 1..find(i + 1, &|i| punct(i, '=') || punct(i, ';'))], names);
+      },
+      proc_macro2::TokenTree::Ident(_) if ident(i, "for") => {
+        collect(&tokens[i + 1..find(i + 1, &|i| ident(i, "in"))], names);
+      },
+      proc_macro2::TokenTree::Ident(_) if ident(i, "fn") => {
+        collect(&tokens[i + 1..(i + 3).min(tokens.len())], names);
+      },
+      // `|params| body`, a `|` which can't be a binary operator
+      proc_macro2::TokenTree::Punct(p) if p.as_char() == '|' && (
+        i == 0 || ident(i - 1, "move") || matches!(tokens[i - 1], proc_macro2::TokenTree::Punct(ref p) if p.as_char() != '|')
+      ) => {
+        collect(&tokens[i + 1..find(i + 1, &|i| punct(i, '|'))], names);
+      },
+      // `pattern => ...`, the guard is taken along with the pattern
+      proc_macro2::TokenTree::Punct(p) if p.as_char() == '=' && punct(i + 1, '>') => {
+        collect(&tokens[arm..i], names);
+        arm = i + 2;
+      },
+      proc_macro2::TokenTree::Punct(p) if p.as_char() == ',' => arm = i + 1,
+      _ => {},
+    }
+  }
+}
+
+
+/// `'label:` in front of a loop.
+fn parse_label(input: ParseStream) -> Result<Option<Label>> {
+  if input.peek(Lifetime) {
+    return Ok(Some(input.parse()?));
+  }
+
+  Ok(None)
+}
+
+/// `break` and `continue` are only parsed by `Control`, reaching them anywhere else means they are not
+/// inside of a flow body.
+fn control_outside_loop(input: ParseStream) -> Error {
+  if input.peek(Token![break]) {
+    let token = input.parse::<Token![break]>().unwrap();
+    return Error::new(token.span, "`break` outside of a loop");
+  }
+
+  let token = input.parse::<Token![continue]>().unwrap();
+  Error::new(token.span, "`continue` outside of a loop")
+}
+
+
+/// `let` statement emitted as-is, so the binding is visible to everything after it in the same scope.
+fn parse_let(input: ParseStream) -> Result<Stmt> {
+  let stmt = input.parse::<Stmt>()?;
+
+  // the names used by the generated code can't be shadowed
+  if let Stmt::Local(Local { pat: Pat::Ident(pat), .. }) = &stmt {
+    if ["spawner", "entity", "this", "parent"].iter().any(|name| pat.ident == name) {
+      return Err(Error::new(pat.ident.span(), format!("`{}` is reserved by the macro", pat.ident)));
+    }
+  }
+
+  Ok(stmt)
+}
+
+
+/// Whether the identifier is found anywhere in the tokens, including in nested groups.
+fn mentions(tokens: &proc_macro2::TokenStream, ident: &str) -> bool {
+  tokens.clone().into_iter().any(|token| match token {
+    proc_macro2::TokenTree::Ident(found) => found == ident,
+    proc_macro2::TokenTree::Group(group) => mentions(&group.stream(), ident),
+    _ => false,
+  })
+}
+
+/// Puts the attributes in front of every statement, as a single top level or child can be generated
+/// as several of them, e.g. the declared captures followed by the entity.
+fn with_attributes(attrs: &[Attribute], tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+  let mut result    = quote! {};
+  let mut statement = quote! {};
+
+  for token in tokens {
+    let is_end = matches!(&token, proc_macro2::TokenTree::Punct(p) if p.as_char() == ';');
+    statement.extend([token]);
+
+    if is_end {
+      result.extend(quote! { #(#attrs)* #statement });
+      statement = quote! {};
+    }
+  }
+
+  if !statement.is_empty() {
+    result.extend(quote! { #(#attrs)* #statement });
+  }
+
+  result
+}
+
+
+/// Whether the next token is the last one of the input.
+fn is_last_token(input: ParseStream) -> bool {
+  input.cursor().token_tree().is_some_and(|(_, rest)| rest.eof())
+}
+
+/// Children group is either `.[ ... ]` or `.children_at(index)[ ... ]`.
+fn peek_children(input: ParseStream) -> bool {
+  if input.peek2(Bracket) {
+    return true;
+  }
+
+  let fork = input.fork();
+  fork.parse::<Token![.]>().is_ok() && fork.parse::<Ident>().is_ok_and(|name| name == "children_at")
+}
+
+
+/// Whether any of the components is prefixed with `+`, i.e. at the start or right after a comma.
+fn has_prefixed(components: &proc_macro2::TokenStream) -> bool {
+  let tokens = components.clone().into_iter().collect::<Vec<_>>();
+  let mut segment_start = true;
+
+  for (i, token) in tokens.iter().enumerate() {
+    // `name: value` is a shorthand, `name::path` is not
+    let is_shorthand = matches!(tokens.get(i + 1), Some(proc_macro2::TokenTree::Punct(p))
+      if p.as_char() == ':' && p.spacing() == proc_macro2::Spacing::Alone);
+
+    match token {
+      proc_macro2::TokenTree::Punct(p) if p.as_char() == '+' && segment_start => return true,
+      proc_macro2::TokenTree::Ident(_) if is_shorthand && segment_start && cfg!(feature = "component-shorthand") => return true,
+      proc_macro2::TokenTree::Punct(p) => segment_start = p.as_char() == ',',
+      _ => segment_start = false,
+    }
+  }
+
+  false
+}
+
+
+/// Shorthands of the single field components, enabled by the `component-shorthand` feature.
+const COMPONENT_SHORTHANDS: &[(&str, &str)] = &[
+  ("bg"    , "bevy::ui::BackgroundColor"),
+  ("border", "bevy::ui::BorderColor"    ),
+  ("color" , "bevy::text::TextColor"    ),
+];
+
+/// Parses a component, expanding `name: value` into `Component(value)` with `component-shorthand`.
+fn parse_component(input: ParseStream) -> Result<Expr> {
+  if !cfg!(feature = "component-shorthand") || !input.peek(Ident) || !input.peek2(Token![:]) || input.peek2(Token![::]) {
+    return input.parse();
+  }
+
+  let name = input.parse::<Ident>()?;
+  input.parse::<Token![:]>()?;
+
+  let Some((_, path)) = COMPONENT_SHORTHANDS.iter().find(|(short, _)| name == short) else {
+    let expected = COMPONENT_SHORTHANDS.iter().map(|(short, _)| format!("`{short}`")).collect::<Vec<_>>();
+    return Err(Error::new(name.span(), format!("Unknown component shorthand, expected one of {}", expected.join(", "))));
+  };
+
+  let path  = parse_str::<Path>(path)?;
+  let value = input.parse::<Expr>()?;
+  Ok(parse_quote_spanned! { name.span() => #path(#value) })
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn expand(input: proc_macro2::TokenStream) -> String {
+    match syn::parse2::<Spawn>(input) {
+      Ok (spawn) => spawn.generate().to_string(),
+      Err(err  ) => panic!("failed to parse: {err}"),
+    }
+  }
+
+  /// The message of a parse error, or the whole expansion when it's reported by the generator.
+  fn error(input: proc_macro2::TokenStream) -> String {
+    match syn::parse2::<Spawn>(input) {
+      Ok (spawn) => spawn.generate().to_string(),
+      Err(err  ) => err.to_string(),
+    }
+  }
+
+  #[test]
+  fn unit_without_return() {
+    assert!(expand(quote! { commands (A(1)) }).ends_with("} ;"));
+    assert!(expand(quote! { commands root (A(1)); }).ends_with("} ;"));
+  }
+
+  #[test]
+  fn entity_with_return() {
+    let expanded = expand(quote! { commands (B); return (A(1)) });
+    assert!(expanded.ends_with("root }"), "{expanded}");
+
+    let expanded = expand(quote! { commands return root (A(1)); });
+    assert!(expanded.ends_with("root }"), "{expanded}");
+  }
+
+  #[test]
+  fn return_errors() {
+    assert_eq!(error(quote! { commands return { foo(); } }), "Only a spawned entity can be returned");
+    assert_eq!(error(quote! { commands return (A); (B); }), "The returned entity has to be the last one");
+    assert_eq!(error(quote! { commands => all root (A); return (B) }), "`return` can't be used together with `=> name`");
+  }
+
+  #[test]
+  fn name_from_another_group_errors() {
+    let expanded = expand(quote! { commands
+      (A).[ uwu (A); ];
+      (A).[ { let x = uwu; }; ];
+    });
+
+    assert_eq!(expanded.matches("compile_error").count(), 1, "{expanded}");
+    assert!(expanded.contains("`uwu` is declared in another children group"), "{expanded}");
+
+    // a binding from outside of the macro can't be told apart, the entity has to be renamed then
+    assert!(expanded.contains("rename the entity if a binding from outside of the macro is meant"), "{expanded}");
+  }
+
+  #[test]
+  fn names_bound_in_code_are_locals() {
+    let locals = [
+      quote! { (A).(|t: Trigger<Ping>| { t.target(); }); },
+      quote! { (A).{ v.iter().map(|t| t * 2); }; },
+      quote! { (A).{ v.iter().map(move |(i, t)| i + t); }; },
+      quote! { (A).{ let (a, t) = pair; }; },
+      quote! { (A).{ if let Some(t) = option { t; } }; },
+      quote! { (A).{ match option { Some(t) if t > 0 => t, _ => 0 }; }; },
+      quote! { (A).{ for t in 0..3 { t; } }; },
+      quote! { (A).{ fn f(t: u32) -> u32 { t } }; },
+      quote! { (A).{ println!("{t}", t = 1); }; },
+      quote! { (A { t: 1 }); },
+      quote! { (A).{ entity.t(); value.t; }; },
+    ];
+
+    for local in locals {
+      let expanded = expand(quote! { commands (A).[ t (A); ]; #local });
+      assert!(!expanded.contains("compile_error"), "{expanded}");
+    }
+  }
+
+  #[test]
+  fn flow_pattern_is_visible_in_body() {
+    let expanded = expand(quote! { commands
+      (A).[ item (A); ];
+
+      for item in items {
+        (A).{ println!("{item}"); };
+      }
+
+      match pair {
+        (index, Some(item)) => { (A).{ println!("{index} {item}"); }; }
+        _ => {}
+      }
+    });
+
+    assert!(!expanded.contains("compile_error"), "{expanded}");
+  }
+
+  #[test]
+  fn bare_tuple_struct_is_rejected() {
+    assert_eq!(
+      error(quote! { commands A(20); }),
+      "`A(...)` would be an entity named `A`, wrap a tuple struct component in parentheses, like `(A(...))`",
+    );
+
+    let expanded = expand(quote! { commands (A(20)); Text::new("hi"); root (A(1)); });
+    assert!(expanded.contains("A (20)"), "{expanded}");
+    assert!(expanded.contains("Text :: new (\"hi\")"), "{expanded}");
+  }
+
+  #[test]
+  fn if_suffix_only_for_known_methods() {
+    let expanded = expand(quote! { commands (A).observe_if(clickable, on_click); });
+    assert!(expanded.contains("if clickable { entity . observe (on_click) ; }"), "{expanded}");
+
+    let expanded = expand(quote! { commands (A).highlight_if(selected); });
+    assert!(expanded.contains("entity . highlight_if (selected) ;"), "{expanded}");
+
+    let expanded = expand(quote! { commands (A).insert_if(B, || selected); });
+    assert!(expanded.contains("entity . insert_if (B , | | selected) ;"), "{expanded}");
+  }
+
+  #[test]
+  fn else_loop_chains() {
+    let expanded = expand(quote! { commands if flag { (A); } else for i in 0..3 { (B(i)); } });
+    assert!(expanded.contains("if flag {"), "{expanded}");
+    assert!(expanded.contains("else { for i in 0 .. 3 {"), "{expanded}");
+
+    let expanded = expand(quote! { commands if let Some(a) = a { (A(a)); } else while n > 0 { (B); } });
+    assert!(expanded.contains("else { while n > 0 {"), "{expanded}");
+
+    let expanded = expand(quote! { commands if flag { (A); } else while let Some(b) = it.next() { (B(b)); } });
+    assert!(expanded.contains("else { # [allow (irrefutable_let_patterns)] while let Some (b) = it . next () {"), "{expanded}");
+  }
+
+  #[test]
+  fn parented_in_top_level_loops() {
+    let expanded = expand(quote! { commands
+      grid (Node::default());
+
+      for row in 0..4 {
+        grid > (A(row));
+      }
+
+      while n > 0 {
+        grid > (B);
+      }
+    });
+
+    assert_eq!(expanded.matches("ChildOf (grid)").count(), 2, "{expanded}");
+  }
+
+  #[test]
+  fn single_component_and_trailing_comma() {
+    // a single component is spawned as itself, with a comma as a 1-tuple, like in Rust
+    assert!(expand(quote! { commands (A); }).contains("spawn (spawner , (A))"));
+    assert!(expand(quote! { commands (A,); }).contains("spawn (spawner , (A ,))"));
+    assert!(expand(quote! { commands (A, B,); }).contains("spawn (spawner , (A , B ,))"));
+  }
+
+  #[test]
+  fn empty_components_spawn_empty() {
+    let expanded = expand(quote! { commands (); (A); });
+    assert!(expanded.contains("Spawnable :: spawn_empty (spawner)"), "{expanded}");
+    assert_eq!(expanded.matches("spawn_empty").count(), 1, "{expanded}");
+  }
+
+  #[test]
+  fn entity_and_global_observers() {
+    let expanded = expand(quote! { commands (A).(on_click).observe_global(on_any_click); });
+    assert!(expanded.contains("entity . observe (on_click)"), "{expanded}");
+    assert!(expanded.contains("entity . commands () . add_observer (on_any_click)"), "{expanded}");
+  }
+
+  #[test]
+  fn else_forms() {
+    let expanded = expand(quote! { commands
+      if a == B::C { (A); } else if let Some(x) = x { (A(x)); } else if b { (B); } else { (C); }
+    });
+
+    assert!(expanded.contains("if a == B :: C {"), "{expanded}");
+    assert!(expanded.contains("else if let Some (x) = x {"), "{expanded}");
+    assert!(expanded.contains("else if b {"), "{expanded}");
+    assert!(expanded.contains("else {"), "{expanded}");
+
+    let expanded = expand(quote! { commands (A).[ if a { (A); } else { (B); } ]; });
+    assert!(expanded.contains("if a {"), "{expanded}");
+  }
+
+  #[test]
+  fn control_in_nested_ifs() {
+    let expanded = expand(quote! { commands for i in 0..3 { if a { if b { continue; } break; } (A); } });
+    assert!(expanded.contains("if a { if b { continue ; } break ; }"), "{expanded}");
+
+    // a loop around the macro doesn't count, the same as for a `break` directly in the top level
+    assert_eq!(error(quote! { commands if a { break; } }), "`break` outside of a loop");
+    assert_eq!(error(quote! { commands (A).[ if a { (B); } else { continue; } ]; }), "`continue` outside of a loop");
+    assert_eq!(error(quote! { commands match a { _ => { (A).[ if b { break; } ]; } } }), "`break` outside of a loop");
+    assert_eq!(error(quote! { commands if a {} else if b { break; } }), "`break` outside of a loop");
+
+    // the children groups of an entity in a loop are still inside of it
+    let expanded = expand(quote! { commands for i in 0..3 { (A).[ if b { break; } (B); ]; } });
+    assert!(expanded.contains("if b { break ; }"), "{expanded}");
+  }
+
+  #[test]
+  fn child_of_conflicts_with_parented() {
+    let expanded = error(quote! { commands root > (A, ChildOf(other)); });
+    assert!(expanded.contains("`ChildOf` conflicts with the parent given by the macro"), "{expanded}");
+
+    let expanded = error(quote! { commands (A) < root; (A, ChildOf(other)); });
+    assert!(!expanded.contains("compile_error"), "{expanded}");
+  }
+
+  #[test]
+  fn this_is_bound_before_observers() {
+    let expanded = expand(quote! { commands (A).(move |_: Trigger<Ping>| { println!("{this}"); }); });
+    let this     = expanded.find("let this = entity . id ()").expect(&expanded);
+    let observe  = expanded.find("entity . observe (move").expect(&expanded);
+    assert!(this < observe, "{expanded}");
+  }
+
+  #[test]
+  #[cfg(not(feature = "entity-markers"))]
+  fn expansion_snapshot() {
+    // the helper names are fixed rather than numbered, the same input expands to the same code
+    let input = quote! { commands
+      root (A).(on_click).[
+        (B);
+        for i in 0..3 { (C(i)); }
+      ];
+      list + ().children_at(0)[ (D); ];
+    };
+
+    let expanded = expand(input.clone());
+    assert_eq!(expanded, expand(input));
+    assert_eq!(expanded, [
+      "{ let spawner = & mut commands ; # [allow (unused_variables , clippy :: shadow_unrelated)]",
+      "let root = { let mut entity = :: bevy_toolbox :: Spawnable :: spawn (spawner , (A)) ; let",
+      "this = entity . id () ; entity . observe (on_click) ; { let parent = this ; { let mut",
+      "entity = :: bevy_toolbox :: Spawnable :: spawn (spawner , (ChildOf (parent) , B)) ; let",
+      "this = entity . id () ; this } ; for i in 0 .. 3 { { let mut entity = :: bevy_toolbox ::",
+      "Spawnable :: spawn (spawner , (ChildOf (parent) , C (i))) ; let this = entity . id () ;",
+      "this } ; } } ; this } ; { let mut entity = :: bevy_toolbox :: Spawnable :: entity (spawner",
+      ", list) ; entity . insert (()) ; let this = entity . id () ; { let parent = this ; let mut",
+      "children = :: std :: vec :: Vec :: < :: bevy_toolbox :: bevy_ecs :: entity :: Entity > :: new () ; #",
+      "[allow (unused_variables , clippy :: shadow_unrelated)] let child = { let mut entity = ::",
+      "bevy_toolbox :: Spawnable :: spawn (spawner , (ChildOf (parent) , D)) ; let this = entity",
+      ". id () ; this } ; children . push (child) ; :: bevy_toolbox :: Spawnable :: entity",
+      "(spawner , parent) . insert_children (0 , & children) ; } ; } ; } ;",
+    ].join(" "));
+  }
+
+  #[test]
+  fn code_blocks_reborrow_through_trait() {
+    let expanded = expand(quote! { world (A).{ entity.insert(B); }; });
+    assert!(expanded.contains("let mut entity = :: bevy_toolbox :: Reborrow :: reborrow (& mut entity) ;"), "{expanded}");
+  }
+
+  #[test]
+  fn parented_in_top_level_ifs() {
+    let expanded = expand(quote! { commands
+      if wide { a > (A); } else { b > (B); }
+      if let Some(x) = x { [x] > (C); }
+    });
+
+    assert!(expanded.contains("(ChildOf (a) , A)"), "{expanded}");
+    assert!(expanded.contains("(ChildOf (b) , B)"), "{expanded}");
+    assert!(expanded.contains("if let Some (x) = x {"), "{expanded}");
+  }
+
+  #[test]
+  fn emission_order() {
+    let expanded = expand(quote! { commands (..spread, A, +B).first().[ (C); ].last(); });
+    let child    = if cfg!(feature = "spawn-related") { "Spawn (((C) ,))" } else { "(ChildOf (parent) , C)" };
+    let order    = [
+      "spawn (spawner , (spread ,))",
+      "entity . insert ((A ,))",
+      "entity . insert (B)",
+      "entity . first ()",
+      child,
+      "entity . last ()",
+    ].map(|part| expanded.find(part).unwrap_or_else(|| panic!("`{part}` missing in {expanded}")));
+
+    assert!(order.is_sorted(), "{expanded}");
+  }
+
+  #[test]
+  fn commands_rebound_only_when_taken() {
+    let rebind = "let mut commands = :: bevy_toolbox :: HasCommands :: commands (& mut entity) ;";
+
+    let expanded = expand(quote! { commands (A).{ commands.spawn(B); }.[ for i in x { (C).{ commands.spawn(D); }; } ]; });
+    assert_eq!(expanded.matches(rebind).count(), 2, "{expanded}");
+
+    // the same blocks next to a `World` spawner keep the user's `commands`
+    let expanded = expand(quote! { world (A).{ commands.spawn(B); }.[ for i in x { (C).{ commands.spawn(D); }; } ]; });
+    assert!(!expanded.contains(rebind), "{expanded}");
+
+    // a block not using `commands` isn't touched either
+    let expanded = expand(quote! { commands (A).{ entity.insert(B); }; });
+    assert!(!expanded.contains(rebind), "{expanded}");
+  }
+
+  #[test]
+  fn pre_is_assigned_by_named_if_and_inserted() {
+    let expanded = expand(quote! { commands (A).[
+      pre icon, base;
+      icon if a { (B) } else { (C) };
+      base = other + (D);
+      { println!("{icon:?} {base:?}"); };
+    ]; });
+
+    assert!(!expanded.contains("let icon ="), "{expanded}");
+    assert!(!expanded.contains("let base ="), "{expanded}");
+    assert!(expanded.contains("icon = { # [allow (irrefutable_let_patterns)] let chosen = if a"), "{expanded}");
+    assert!(expanded.contains("base = {"), "{expanded}");
+
+    // the same with `pre` inside of a flow body
+    let expanded = expand(quote! { commands (A).[
+      for i in 0..3 { pre icon; icon if i > 0 { (B) } else { (C) }; { println!("{icon:?}"); }; }
+    ]; });
+
+    assert!(!expanded.contains("let icon ="), "{expanded}");
+    assert!(expanded.contains("let chosen = if i > 0"), "{expanded}");
+  }
+}
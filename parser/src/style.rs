@@ -0,0 +1,197 @@
+//! # Grammar
+//!
+//! * `<TOKEN>*` means repeat 0-inf times separated by `TOKEN`, the last `TOKEN` is optional.
+//!
+//! ```txt
+//! style ::= (property ':' value)<';'>* ;
+//!
+//! property ::= IDENT<'-'>+ ;
+//! value    ::= v | e | IDENT<'-'>+ ;
+//! ```
+use crate::*;
+use syn::ext::IdentExt;
+use value::*;
+use edges::*;
+
+
+/// The CSS-like properties and the fields of `Node` they set, a new property only needs a new row.
+const PROPERTIES: &[(&str, &str, Kind)] = &[
+  ("width"          , "width"          , Kind::Value),
+  ("height"         , "height"         , Kind::Value),
+  ("min-width"      , "min_width"      , Kind::Value),
+  ("min-height"     , "min_height"     , Kind::Value),
+  ("max-width"      , "max_width"      , Kind::Value),
+  ("max-height"     , "max_height"     , Kind::Value),
+  ("row-gap"        , "row_gap"        , Kind::Value),
+  ("column-gap"     , "column_gap"     , Kind::Value),
+  ("padding"        , "padding"        , Kind::Edges),
+  ("margin"         , "margin"         , Kind::Edges),
+  ("border"         , "border"         , Kind::Edges),
+  ("display"        , "display"        , Kind::Keyword("Display")),
+  ("position-type"  , "position_type"  , Kind::Keyword("PositionType")),
+  ("flex-direction" , "flex_direction" , Kind::Keyword("FlexDirection")),
+  ("flex-wrap"      , "flex_wrap"      , Kind::Keyword("FlexWrap")),
+  ("justify-content", "justify_content", Kind::Keyword("JustifyContent")),
+  ("align-items"    , "align_items"    , Kind::Keyword("AlignItems")),
+  ("align-content"  , "align_content"  , Kind::Keyword("AlignContent")),
+];
+
+#[derive(Clone, Copy)]
+enum Kind {
+  /// A single `v!` value.
+  Value,
+  /// The `e!` sides.
+  Edges,
+  /// A kebab-case variant of the given enum, e.g. `space-between` for `JustifyContent::SpaceBetween`.
+  Keyword(&'static str),
+}
+
+
+pub struct Style {
+  properties: Vec<Property>,
+}
+
+impl Parse for Style {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let mut properties = vec![];
+
+    while !input.is_empty() {
+      if input.peek(Token![;]) {
+        input.parse::<Token![;]>()?;
+        continue;
+      }
+
+      properties.push(input.parse()?);
+    }
+
+    Ok(Style { properties })
+  }
+}
+
+impl Generate for Style {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let properties = self.properties.iter().map(Property::generate);
+
+    quote! {
+      bevy::ui::Node {
+        #(#properties,)*
+        ..::core::default::Default::default()
+      }
+    }
+  }
+}
+
+
+enum PropertyValue {
+  Value  (Value),
+  Edges  (Edges),
+  Keyword(Ident, Ident),
+}
+
+struct Property {
+  field: Ident,
+  value: PropertyValue,
+}
+
+impl Parse for Property {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let (name, span) = parse_kebab(input)?;
+    input.parse::<Token![:]>()?;
+
+    let Some((_, field, kind)) = PROPERTIES.iter().find(|(property, ..)| *property == name) else {
+      let message = match suggest(&name) {
+        Some(similar) => format!("Unknown property `{name}`, did you mean `{similar}`?"),
+        None          => format!("Unknown property `{name}`"),
+      };
+
+      return Err(Error::new(span, message));
+    };
+
+    // the value ends at the next ';', so `e!` doesn't run into the next property
+    let mut tokens = proc_macro2::TokenStream::new();
+    while !input.is_empty() && !input.peek(Token![;]) {
+      tokens.extend([input.parse::<proc_macro2::TokenTree>()?]);
+    }
+
+    if tokens.is_empty() {
+      return Err(input.error(format!("Expected value for `{name}`")));
+    }
+
+    let value = match kind {
+      Kind::Value => PropertyValue::Value(syn::parse2(tokens)?),
+      Kind::Edges => PropertyValue::Edges(syn::parse2(tokens)?),
+      Kind::Keyword(ty) => {
+        let (variant, span) = parse_kebab.parse2(tokens)?;
+        PropertyValue::Keyword(Ident::new(ty, span), Ident::new(&pascal_case(&variant), span))
+      },
+    };
+
+    Ok(Property { field: Ident::new(field, span), value })
+  }
+}
+
+impl Generate for Property {
+  fn generate(&self) -> proc_macro2::TokenStream {
+    let Property { field, value } = self;
+
+    let value = match value {
+      PropertyValue::Value  (value        ) => value.generate(),
+      PropertyValue::Edges  (edges        ) => edges.generate(),
+      PropertyValue::Keyword(ty, variant) => quote! { bevy::ui::#ty::#variant },
+    };
+
+    quote! { #field: #value }
+  }
+}
+
+
+/// Parses `a-b-c`, the span is the one of the first part.
+fn parse_kebab(input: ParseStream) -> Result<(String, Span)> {
+  let first = input.call(Ident::parse_any)?;
+  let mut name = first.to_string();
+
+  while input.peek(Token![-]) {
+    input.parse::<Token![-]>()?;
+    name.push('-');
+    name.push_str(&input.call(Ident::parse_any)?.to_string());
+  }
+
+  Ok((name, first.span()))
+}
+
+fn pascal_case(kebab: &str) -> String {
+  kebab.split('-').map(|part| {
+    let mut chars = part.chars();
+    chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+  }).collect()
+}
+
+/// The known property closest to `name`, if it's close enough to be a typo.
+fn suggest(name: &str) -> Option<&'static str> {
+  fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+      let mut prev = row[0];
+      row[0] = i + 1;
+
+      for (j, cb) in b.iter().enumerate() {
+        let next = (row[j + 1] + 1).min(row[j] + 1).min(prev + usize::from(ca != *cb));
+        prev = row[j + 1];
+        row[j + 1] = next;
+      }
+    }
+
+    row[b.len()]
+  }
+
+  // `flex_direction` or `flexDirection` are likely meant as `flex-direction`
+  let normalized = name.replace('_', "-").to_lowercase();
+
+  PROPERTIES.iter()
+    .map(|(property, ..)| (*property, distance(&normalized, property).min(distance(name, property))))
+    .filter(|(_, distance)| *distance <= 3)
+    .min_by_key(|(_, distance)| *distance)
+    .map(|(property, _)| property)
+}
@@ -18,6 +18,7 @@
 //!   | '{' EXPR '}' 'vh'
 //!   | '{' EXPR '}' 'vmin'
 //!   | '{' EXPR '}' 'vmax'
+//!   | STRING // any of the above without EXPR
 //!   ;
 //!
 //! number ::= INT | FLOAT ;
@@ -25,6 +26,9 @@
 use crate::*;
 
 
+const UNITS: &str = "expected px, vw, vh, vmin, vmax or %";
+
+
 #[derive(Clone)]
 pub enum Value {
   Auto       (Span),
@@ -52,6 +56,18 @@ impl Parse for Value {
       };
     }
 
+    // the same value written in a string, e.g. loaded from a theme, spans point to the string
+    if input.peek(LitStr) {
+      let lit   = input.parse::<LitStr>()?;
+      let value = lit.parse::<Value>()?;
+
+      if value.is_expr() {
+        return Err(Error::new(lit.span(), "Expressions can't be written in a string value"));
+      }
+
+      return Ok(value);
+    }
+
     if input.peek(Token![@]) {
       let sym = input.parse::<Token![@]>()?;
       return Ok(Value::Auto(sym.span));
@@ -74,11 +90,12 @@ impl Parse for Value {
           "vh"   => Ok(Value::ExprVh     (ident.span(), content)),
           "vmin" => Ok(Value::ExprVMin   (ident.span(), content)),
           "vmax" => Ok(Value::ExprVMax   (ident.span(), content)),
-          _      => Err(Error::new(ident.span(), "Invalid unit, expected px, vw, vh, vmin, vmax or %")),
+          unit   => Err(Error::new(ident.span(), format!("Invalid unit `{unit}`, {UNITS}"))),
         };
       }
 
-      return Err(Error::new(group.span(), "Expected unit, expected px, vw, vh, vmin, vmax or %"));
+      // the unit is expected right after the closing brace
+      return Err(Error::new(group.span_close(), format!("Expected unit after `}}`, {UNITS}")));
     }
 
     let (span, value, unit) = if input.peek(LitFloat) {
@@ -106,7 +123,8 @@ impl Parse for Value {
       "vh"   => Ok(Value::Vh  (span, value)),
       "vmin" => Ok(Value::VMin(span, value)),
       "vmax" => Ok(Value::VMax(span, value)),
-      _ => Err(Error::new(span, "Invalid unit, expected px, vw, vh, vmin, vmax or %")),
+      ""   => Err(Error::new(span, format!("Expected unit after the number, {UNITS}"))),
+      unit => Err(Error::new(span, format!("Invalid unit `{unit}`, {UNITS}"))),
     }
   }
 }
@@ -140,12 +158,19 @@ impl Generate for Value {
     quote! { bevy::ui::Val::#unit #value }
   }
 
+  // `Val::Auto`, so an omitted side of `e!` is `auto` like in CSS, rather than the `0px` of `UiRect::default()`
   fn generate_default() -> proc_macro2::TokenStream {
     quote! { bevy::ui::Val::default() }
   }
 }
 
 impl Value {
+  fn is_expr(&self) -> bool {
+    matches!(self,
+      Value::ExprPx  (..) | Value::ExprVw  (..) | Value::ExprVh     (..) |
+      Value::ExprVMin(..) | Value::ExprVMax(..) | Value::ExprPercent(..))
+  }
+
   pub fn span(&self) -> &Span {
     match self {
       Value::Auto       (span    ) => span,
@@ -164,3 +189,29 @@ impl Value {
     }
   }
 }
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn error(input: proc_macro2::TokenStream) -> String {
+    match syn::parse2::<Value>(input) {
+      Ok (value) => panic!("expected an error, got {}", value.generate()),
+      Err(err  ) => err.to_string(),
+    }
+  }
+
+  #[test]
+  fn expression_units() {
+    assert_eq!(error(quote! { {x} }), "Expected unit after `}`, expected px, vw, vh, vmin, vmax or %");
+    assert_eq!(error(quote! { {x}rem }), "Invalid unit `rem`, expected px, vw, vh, vmin, vmax or %");
+    assert_eq!(syn::parse2::<Value>(quote! { {x}px }).unwrap().generate().to_string(), "bevy :: ui :: Val :: Px ({ x })");
+  }
+
+  #[test]
+  fn number_units() {
+    assert_eq!(error(quote! { 10 }), "Expected unit after the number, expected px, vw, vh, vmin, vmax or %");
+    assert_eq!(error(quote! { 10rem }), "Invalid unit `rem`, expected px, vw, vh, vmin, vmax or %");
+  }
+}
@@ -0,0 +1,101 @@
+//! A runtime counterpart of `spawn!`, for the trees which aren't known at compile time.
+//!
+//! Every method maps to a part of the macro:
+//!
+//! | Builder                           | `spawn!`               |
+//! | --------------------------------- | ---------------------- |
+//! | `SpawnBuilder::spawn(c, (...))`   | `spawn! { c (...) }`   |
+//! | `SpawnBuilder::extend(entity)`    | `insert! { entity }`   |
+//! | `.name("label")`                  | `#"label" (...)`       |
+//! | `.parent(p)`                      | `p > (...)`            |
+//! | `.insert((...))`                  | `.insert(...)`         |
+//! | `.observe(observer)`              | `.(observer)`          |
+//! | `.child((...), build)`            | `.[ (...) ... ]`       |
+//! | `.id()`                           | `this`                 |
+//! | `.entity()`                       | `entity`               |
+use std::borrow::Cow;
+
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::EntityCommands;
+use bevy_ecs::system::IntoObserverSystem;
+
+
+/// Spawns an entity and extends it in place, mirroring the definition syntax of `spawn!`.
+///
+/// Unlike the macro, the builder only works with `Commands`, not with any
+/// [`Spawnable`](crate::Spawnable). With a `World`, spawn through `world.commands()` and apply them
+/// with `world.flush()`.
+///
+/// ```rs, no_run
+/// fn menu(mut commands: Commands, items: Vec<String>) {
+///   let mut menu = SpawnBuilder::spawn(&mut commands, Node::default());
+///   menu.name("menu");
+///
+///   for item in items {
+///     menu.child((Button, Node::default()), |button| {
+///       button
+///         .observe(|_: Trigger<Pointer<Click>>| println!("Clicked!"))
+///         .child(Text::new(item), |_| {});
+///     });
+///   }
+/// }
+/// ```
+pub struct SpawnBuilder<'a> {
+  entity: EntityCommands<'a>,
+}
+
+impl<'a> SpawnBuilder<'a> {
+  /// Spawns a new entity with the given bundle.
+  pub fn spawn(commands: &'a mut Commands, bundle: impl Bundle) -> Self {
+    SpawnBuilder { entity: commands.spawn(bundle) }
+  }
+
+  /// Extends an existing entity, like `insert!`.
+  pub fn extend(entity: EntityCommands<'a>) -> Self {
+    SpawnBuilder { entity }
+  }
+
+  /// The spawned entity, the same as `this` in the macro.
+  pub fn id(&self) -> Entity {
+    self.entity.id()
+  }
+
+  /// The handle of the spawned entity, the same as `entity` in the macro.
+  pub fn entity(&mut self) -> EntityCommands<'_> {
+    self.entity.reborrow()
+  }
+
+  /// Labels the entity with a `Name`.
+  pub fn name(&mut self, name: impl Into<Cow<'static, str>>) -> &mut Self {
+    self.entity.insert(Name::new(name));
+    self
+  }
+
+  /// Places the entity under `parent`.
+  pub fn parent(&mut self, parent: Entity) -> &mut Self {
+    self.entity.insert(ChildOf(parent));
+    self
+  }
+
+  /// Inserts more components into the entity.
+  pub fn insert(&mut self, bundle: impl Bundle) -> &mut Self {
+    self.entity.insert(bundle);
+    self
+  }
+
+  /// Adds an observer watching the events targeting the entity.
+  pub fn observe<E: Event, B: Bundle, M>(&mut self, observer: impl IntoObserverSystem<E, B, M>) -> &mut Self {
+    self.entity.observe(observer);
+    self
+  }
+
+  /// Spawns a child with the given bundle, `build` extends the child the same way.
+  pub fn child(&mut self, bundle: impl Bundle, build: impl FnOnce(&mut SpawnBuilder)) -> &mut Self {
+    let parent = self.id();
+    let mut commands = self.entity.commands();
+
+    // the parent is spawned along with the bundle, the same as a children group of the macro
+    build(&mut SpawnBuilder { entity: commands.spawn((ChildOf(parent), bundle)) });
+    self
+  }
+}
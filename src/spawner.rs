@@ -0,0 +1,214 @@
+//! Capabilities the `spawn!` macro expects from its spawner.
+//!
+//! The generated code never calls the methods of the spawner directly, it goes through [`Spawnable`]
+//! instead. This way, using something that can't spawn entities as the spawner is reported as a
+//! missing trait implementation rather than a missing method somewhere inside the generated code.
+use bevy_ecs::prelude::*;
+use bevy_ecs::bundle::NoBundleEffect;
+use bevy_ecs::system::IntoObserverSystem;
+use bevy_ecs::system::EntityCommands;
+use bevy_ecs::world::EntityWorldMut;
+
+
+/// The object that `spawn!` uses to spawn and look up entities.
+///
+/// Implemented for `Commands` and `World`, and for any mutable reference to them. A spawner given as
+/// an identifier is borrowed mutably, so `spawn! { world ... }` with a `&mut World` needs the binding
+/// to be `mut world: &mut World`, otherwise the reference is passed as is with `[&mut *world]`.
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` can't be used as the spawner of `spawn!`",
+  label   = "not a spawner",
+  note    = "the spawner must be `Commands` or `World`, use `[expr]` to pass an expression",
+  note    = "inside `with_children` or `with_related`, use `[parent.commands()]` as the spawner",
+)]
+pub trait Spawnable {
+  /// The handle of a single entity, e.g. `EntityCommands` for `Commands`.
+  type Entity<'a> where Self: 'a;
+
+  /// Spawns a new entity with the given bundle.
+  fn spawn<B: Bundle>(&mut self, bundle: B) -> Self::Entity<'_>;
+
+  /// Spawns a new entity without any component.
+  fn spawn_empty(&mut self) -> Self::Entity<'_>;
+
+  /// Spawns an entity for each bundle of the iterator at once, which is faster than spawning them
+  /// one by one.
+  fn spawn_batch<I>(&mut self, batch: I)
+  where
+    I: IntoIterator + Send + Sync + 'static,
+    I::Item: Bundle<Effect: NoBundleEffect>;
+
+  /// Gets the handle of an existing entity.
+  fn entity(&mut self, entity: Entity) -> Self::Entity<'_>;
+
+  /// Spawns a global observer, which watches the event triggered on any entity.
+  fn add_observer<E: Event, B: Bundle, M>(
+    &mut self,
+    observer: impl IntoObserverSystem<E, B, M>,
+  ) -> Self::Entity<'_>;
+}
+
+impl Spawnable for Commands<'_, '_> {
+  type Entity<'a> = EntityCommands<'a> where Self: 'a;
+
+  fn spawn<B: Bundle>(&mut self, bundle: B) -> Self::Entity<'_> {
+    Commands::spawn(self, bundle)
+  }
+
+  fn spawn_empty(&mut self) -> Self::Entity<'_> {
+    Commands::spawn_empty(self)
+  }
+
+  fn spawn_batch<I>(&mut self, batch: I)
+  where
+    I: IntoIterator + Send + Sync + 'static,
+    I::Item: Bundle<Effect: NoBundleEffect>,
+  {
+    Commands::spawn_batch(self, batch)
+  }
+
+  fn entity(&mut self, entity: Entity) -> Self::Entity<'_> {
+    Commands::entity(self, entity)
+  }
+
+  fn add_observer<E: Event, B: Bundle, M>(
+    &mut self,
+    observer: impl IntoObserverSystem<E, B, M>,
+  ) -> Self::Entity<'_> {
+    Commands::add_observer(self, observer)
+  }
+}
+
+impl Spawnable for World {
+  type Entity<'a> = EntityWorldMut<'a>;
+
+  fn spawn<B: Bundle>(&mut self, bundle: B) -> Self::Entity<'_> {
+    World::spawn(self, bundle)
+  }
+
+  fn spawn_empty(&mut self) -> Self::Entity<'_> {
+    World::spawn_empty(self)
+  }
+
+  fn spawn_batch<I>(&mut self, batch: I)
+  where
+    I: IntoIterator + Send + Sync + 'static,
+    I::Item: Bundle<Effect: NoBundleEffect>,
+  {
+    // the remaining entities are spawned when the iterator is dropped
+    World::spawn_batch(self, batch);
+  }
+
+  fn entity(&mut self, entity: Entity) -> Self::Entity<'_> {
+    World::entity_mut(self, entity)
+  }
+
+  fn add_observer<E: Event, B: Bundle, M>(
+    &mut self,
+    observer: impl IntoObserverSystem<E, B, M>,
+  ) -> Self::Entity<'_> {
+    World::add_observer(self, observer)
+  }
+}
+
+impl<T: Spawnable + ?Sized> Spawnable for &mut T {
+  type Entity<'a> = T::Entity<'a> where Self: 'a;
+
+  fn spawn<B: Bundle>(&mut self, bundle: B) -> Self::Entity<'_> {
+    T::spawn(self, bundle)
+  }
+
+  fn spawn_empty(&mut self) -> Self::Entity<'_> {
+    T::spawn_empty(self)
+  }
+
+  fn spawn_batch<I>(&mut self, batch: I)
+  where
+    I: IntoIterator + Send + Sync + 'static,
+    I::Item: Bundle<Effect: NoBundleEffect>,
+  {
+    T::spawn_batch(self, batch)
+  }
+
+  fn entity(&mut self, entity: Entity) -> Self::Entity<'_> {
+    T::entity(self, entity)
+  }
+
+  fn add_observer<E: Event, B: Bundle, M>(
+    &mut self,
+    observer: impl IntoObserverSystem<E, B, M>,
+  ) -> Self::Entity<'_> {
+    T::add_observer(self, observer)
+  }
+}
+
+
+/// Reborrows the handle of an entity, so a code block extension can take `entity` by value without
+/// consuming the one used by the rest of the definition.
+///
+/// `EntityCommands` is reborrowed as another `EntityCommands`, while `EntityWorldMut`, which can't be
+/// reborrowed, is handed out as a mutable reference.
+pub trait Reborrow {
+  /// The reborrowed handle.
+  type Output<'a> where Self: 'a;
+
+  /// Reborrows the handle.
+  fn reborrow(&mut self) -> Self::Output<'_>;
+}
+
+impl Reborrow for EntityCommands<'_> {
+  type Output<'a> = EntityCommands<'a> where Self: 'a;
+
+  fn reborrow(&mut self) -> Self::Output<'_> {
+    EntityCommands::reborrow(self)
+  }
+}
+
+impl<'w> Reborrow for EntityWorldMut<'w> {
+  type Output<'a> = &'a mut EntityWorldMut<'w> where Self: 'a;
+
+  fn reborrow(&mut self) -> Self::Output<'_> {
+    self
+  }
+}
+
+
+/// Gives a code block extension the `commands` of the entity, next to the `entity` itself.
+///
+/// `EntityCommands` hands out its `Commands`, while `EntityWorldMut` has none to hand out and gives
+/// [`NoCommands`] instead, the world is reached with `entity.world_scope(...)` there.
+pub trait HasCommands {
+  /// The handed out commands.
+  type Commands<'a> where Self: 'a;
+
+  /// Gets the commands, they borrow the handle until they are no longer used.
+  fn commands(&mut self) -> Self::Commands<'_>;
+}
+
+impl HasCommands for EntityCommands<'_> {
+  type Commands<'a> = Commands<'a, 'a> where Self: 'a;
+
+  fn commands(&mut self) -> Self::Commands<'_> {
+    EntityCommands::commands(self)
+  }
+}
+
+impl HasCommands for EntityWorldMut<'_> {
+  type Commands<'a> = NoCommands where Self: 'a;
+
+  fn commands(&mut self) -> Self::Commands<'_> {
+    NoCommands
+  }
+}
+
+impl<T: HasCommands + ?Sized> HasCommands for &mut T {
+  type Commands<'a> = T::Commands<'a> where Self: 'a;
+
+  fn commands(&mut self) -> Self::Commands<'_> {
+    T::commands(self)
+  }
+}
+
+/// The `commands` of a code block extension when the spawner is a `World` bound as `commands`, which
+/// has no `Commands` to hand out.
+pub struct NoCommands;
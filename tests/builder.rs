@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use bevy_toolbox::prelude::*;
+
+
+#[derive(Component)]
+struct A(i32);
+
+
+#[test]
+fn builds_a_hierarchy() {
+  let mut world = World::new();
+
+  let root = {
+    let mut commands = world.commands();
+    let mut root     = SpawnBuilder::spawn(&mut commands, A(0));
+    root.name("root");
+
+    for i in 1..=2 {
+      root.child(A(i), |child| {
+        child.child(A(i * 10), |_| {});
+      });
+    }
+
+    root.id()
+  };
+  world.flush();
+
+  assert_eq!(world.get::<Name>(root).unwrap().as_str(), "root");
+
+  let children = world.get::<Children>(root).unwrap().to_vec();
+  let values   = children.iter().map(|&child| world.get::<A>(child).unwrap().0).collect::<Vec<_>>();
+  assert_eq!(values, [1, 2]);
+
+  for (&child, value) in children.iter().zip([10, 20]) {
+    let grandchild = world.get::<Children>(child).unwrap()[0];
+    assert_eq!(world.get::<A>(grandchild).unwrap().0, value);
+    assert_eq!(world.get::<ChildOf>(grandchild).unwrap().parent(), child);
+  }
+}
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+use bevy_toolbox::prelude::*;
+
+
+#[test]
+fn omitted_sides_are_auto() {
+  assert_eq!(e!(10px _ 20px), UiRect {
+    top   : Val::Px(10.0),
+    right : Val::Auto,
+    bottom: Val::Px(20.0),
+    left  : Val::Auto,
+  });
+
+  assert_eq!(e!(logical: start 10px), UiRect {
+    top   : Val::Auto,
+    right : Val::Auto,
+    bottom: Val::Auto,
+    left  : Val::Px(10.0),
+  });
+}
@@ -0,0 +1,45 @@
+// the expansions have to stay clean in crates with strict lint setups
+#![deny(warnings)]
+
+use bevy::prelude::*;
+use bevy::ecs::system::RunSystemOnce;
+use bevy_toolbox::prelude::*;
+
+
+#[derive(Component)]
+struct Item(usize);
+
+#[derive(Event)]
+struct Ping;
+
+
+#[test]
+fn expansions_without_warnings() {
+  fn system(mut commands: Commands) {
+    let items = [1, 2];
+    let show  = true;
+
+    spawn! { commands
+      // names used only for readability, and reused across groups
+      root (Node { margin: e!(10px _ 20px), width: v!(50%), ..Default::default() })
+        .observe(|_: Trigger<Ping>| {})
+        .[
+          for i in items.iter().copied() {
+            item (Item(i), BackgroundColor(c!(#333)));
+          }
+          if show {
+            (Text::new("shown"));
+          } else {
+            (Text::new("hidden"));
+          }
+          { let _ = 1; }
+        ];
+
+      root (Node::default()).{ entity.insert(Item(0)); };
+    }
+  }
+
+  let mut world = World::new();
+  world.run_system_once(system).unwrap();
+  assert_eq!(world.query::<&Item>().iter(&world).map(|item| item.0).sum::<usize>(), 3);
+}
@@ -0,0 +1,809 @@
+use bevy::prelude::*;
+use bevy::ecs::system::RunSystemOnce;
+use bevy_toolbox::prelude::*;
+
+
+#[derive(Component)]
+struct A(i32);
+
+
+fn run<M>(system: impl IntoSystem<(), (), M>) -> World {
+  let mut world = World::new();
+  world.run_system_once(system).unwrap();
+  world
+}
+
+/// The `A` of the children of the entity with `A(value)`, in order.
+fn children_of(world: &mut World, value: i32) -> Vec<i32> {
+  let parent = world.query::<(Entity, &A)>().iter(world).find(|(_, a)| a.0 == value).unwrap().0;
+  world.get::<Children>(parent).unwrap().iter().map(|child| world.get::<A>(child).unwrap().0).collect()
+}
+
+
+#[test]
+fn unit_without_return() {
+  // the last entity without `;` doesn't make the macro an expression
+  fn system(mut commands: Commands) {
+    spawn! { commands (A(1)) }
+  }
+
+  let mut world = run(system);
+  assert_eq!(world.query::<&A>().iter(&world).count(), 1);
+}
+
+#[test]
+fn entity_with_return() {
+  fn system(mut commands: Commands) {
+    let root = spawn! { commands
+      return (A(1)).[
+        (A(2));
+      ];
+    };
+
+    spawn! { commands
+      (A(3), ChildOf(root));
+    }
+  }
+
+  let mut world = run(system);
+  let root = world.query::<(Entity, &A)>().iter(&world).find(|(_, a)| a.0 == 1).unwrap().0;
+  assert_eq!(world.get::<Children>(root).unwrap().len(), 2);
+}
+
+#[test]
+fn code_bindings_named_like_entity() {
+  // closure parameters and patterns in the code are locals, not the entities of the other group
+  #[deny(warnings)]
+  fn system(mut commands: Commands) {
+    let values = [1, 2];
+
+    spawn! { commands
+      (A(0)).[ t (A(1)); x (A(2)); ];
+      (A(3)).(|t: Trigger<Ping>, mut commands: Commands| { commands.entity(t.target()).insert(Pinged); });
+      (A(4)).{ entity.insert(A(values.iter().map(|x| x * 2).sum())); };
+    }
+  }
+
+  let mut world = run(system);
+  ping(&mut world, 3);
+  assert!(pinged(&mut world, 3));
+  assert!(world.query::<&A>().iter(&world).any(|a| a.0 == 6));
+}
+
+#[test]
+fn loop_binding_named_like_entity() {
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      (A(0)).[ item (A(-1)); ];
+
+      for item in 1..=3 {
+        (A(0)).{ entity.insert(A(item)); };
+      }
+    }
+  }
+
+  let mut world = run(system);
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [-1, 0, 1, 2, 3]);
+}
+
+#[test]
+fn else_loop_chains() {
+  fn system(mut commands: Commands) {
+    let flag = false;
+    let mut n = 2;
+    let mut it = [10, 20].into_iter();
+
+    spawn! { commands
+      if flag {
+        (A(0));
+      } else for i in 1..=3 {
+        (A(i));
+      }
+
+      if let Some(a) = None::<i32> {
+        (A(a));
+      } else while n > 0 {
+        (A(n + 3));
+        { n -= 1; };
+      }
+
+      if flag {
+        (A(0));
+      } else while let Some(b) = it.next() {
+        (A(b));
+      }
+    }
+  }
+
+  let mut world = run(system);
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [1, 2, 3, 4, 5, 10, 20]);
+}
+
+#[test]
+fn parented_in_top_level_loops() {
+  // mirrors the rows of the `forty_buttons` example
+  fn system(mut commands: Commands) {
+    let mut n = 2;
+
+    spawn! { commands
+      grid (A(0));
+
+      for row in 1..=4 {
+        grid > (A(row)).[
+          for col in 0..10 {
+            (A(row * 10 + col));
+          }
+        ];
+      }
+
+      while n > 0 {
+        grid > (A(-n));
+        { n -= 1; };
+      }
+    }
+  }
+
+  let mut world = run(system);
+  let grid = world.query::<(Entity, &A)>().iter(&world).find(|(_, a)| a.0 == 0).unwrap().0;
+  let rows = world.get::<Children>(grid).unwrap().to_vec();
+  assert_eq!(rows.len(), 6);
+
+  for row in &rows[..4] {
+    assert_eq!(world.get::<Children>(*row).unwrap().len(), 10);
+  }
+}
+
+#[derive(Event)]
+struct Ping;
+
+#[derive(Component)]
+struct Pinged;
+
+
+fn ping(world: &mut World, value: i32) {
+  let target = world.query::<(Entity, &A)>().iter(world).find(|(_, a)| a.0 == value).unwrap().0;
+  world.trigger_targets(Ping, target);
+  world.flush();
+}
+
+fn pinged(world: &mut World, value: i32) -> bool {
+  world.query_filtered::<&A, With<Pinged>>().iter(world).any(|a| a.0 == value)
+}
+
+
+#[test]
+fn inserted_with_observer_and_children() {
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      root (A(1));
+
+      root + (Name::new("root"))
+        .(|trigger: Trigger<Ping>, mut commands: Commands| {
+          commands.entity(trigger.target()).insert(Pinged);
+        })
+        .{ entity.insert(Visibility::Hidden); }
+        .[
+          (A(2));
+          (A(3));
+        ];
+    }
+  }
+
+  let mut world = run(system);
+  let root = world.query::<(Entity, &A)>().iter(&world).find(|(_, a)| a.0 == 1).unwrap().0;
+  assert_eq!(world.get::<Children>(root).unwrap().len(), 2);
+  assert_eq!(world.get::<Visibility>(root), Some(&Visibility::Hidden));
+  assert!(world.get::<Name>(root).is_some());
+
+  ping(&mut world, 1);
+  assert!(pinged(&mut world, 1));
+}
+
+#[test]
+fn single_component_and_trailing_comma() {
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      (A(1));
+      (A(2),);
+      (A(3), Name::new("three"),);
+    }
+  }
+
+  let mut world = run(system);
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [1, 2, 3]);
+  assert_eq!(world.query::<&Name>().iter(&world).count(), 1);
+}
+
+#[test]
+fn empty_components() {
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      ().{ entity.insert(A(1)); };
+    }
+  }
+
+  let mut world = run(system);
+  spawn! { [&mut world] ().{ entity.insert(A(2)); }; }
+
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [1, 2]);
+}
+
+#[test]
+fn control_in_code_blocks() {
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      for i in 0..10 {
+        {
+          if i == 2 { continue; }
+          if i == 5 { break; }
+        };
+
+        (A(i)).{
+          if i == 3 { continue; }
+          entity.insert(Name::new("kept"));
+        };
+      }
+    }
+  }
+
+  let mut world = run(system);
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [0, 1, 3, 4]);
+  assert_eq!(world.query::<&Name>().iter(&world).count(), 3);
+}
+
+#[test]
+fn entity_and_global_observers() {
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      (A(1)).(|trigger: Trigger<Ping>, mut commands: Commands| {
+        commands.entity(trigger.target()).insert(Pinged);
+      });
+
+      (A(2)).observe_global(|trigger: Trigger<Ping>, mut commands: Commands| {
+        commands.entity(trigger.target()).insert(Pinged);
+      });
+
+      (A(3));
+    }
+  }
+
+  let mut world = run(system);
+
+  // the entity observer only reacts to its own entity, the global one to any
+  ping(&mut world, 3);
+  assert!(pinged(&mut world, 3));
+  assert!(!pinged(&mut world, 1));
+
+  ping(&mut world, 1);
+  assert!(pinged(&mut world, 1));
+  assert!(!pinged(&mut world, 2));
+}
+
+#[test]
+fn else_forms_in_children() {
+  #[derive(PartialEq)]
+  enum Mode { Wide, Narrow }
+
+  fn system(mut commands: Commands) {
+    let mode  = Mode::Narrow;
+    let value = Some(4);
+    let mut n = 1;
+    let mut it = [7].into_iter();
+
+    spawn! { commands
+      root (A(0)).[
+        if mode == Mode::Wide {
+          (A(-1));
+        } else if let Some(v) = value {
+          (A(v));
+        } else {
+          (A(-2));
+        }
+
+        if mode == Mode::Wide {
+          (A(-3));
+        } else if value.is_none() {
+          (A(-4));
+        } else {
+          (A(5));
+        }
+
+        if value.is_none() {
+          (A(-5));
+        } else for i in 1..=2 {
+          (A(i));
+        }
+
+        if value.is_none() {
+          (A(-6));
+        } else while n > 0 {
+          (A(6));
+          { n -= 1; };
+        }
+
+        if value.is_none() {
+          (A(-7));
+        } else while let Some(v) = it.next() {
+          (A(v));
+        }
+      ];
+    }
+  }
+
+  let mut world = run(system);
+  let root = world.query::<(Entity, &A)>().iter(&world).find(|(_, a)| a.0 == 0).unwrap().0;
+  let mut values = world.get::<Children>(root).unwrap().iter()
+    .map(|child| world.get::<A>(child).unwrap().0)
+    .collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [1, 2, 4, 5, 6, 7]);
+}
+
+#[test]
+fn control_in_nested_ifs() {
+  fn system(mut commands: Commands) {
+    let mut n = 0;
+
+    spawn! { commands
+      for i in 0..10 {
+        if i % 3 == 1 {
+          continue;
+        }
+
+        if i > 2 {
+          if i == 8 {
+            break;
+          }
+        }
+
+        (A(i));
+      }
+
+      while n < 10 {
+        { n += 1; };
+
+        if n % 2 == 0 {
+          if n > 4 {
+            break;
+          }
+
+          continue;
+        }
+
+        (A(100 + n));
+      }
+    }
+  }
+
+  let mut world = run(system);
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [0, 2, 3, 5, 6, 101, 103, 105]);
+}
+
+#[test]
+fn parent_by_child_of_component() {
+  fn system(mut commands: Commands) {
+    let root = commands.spawn(A(0)).id();
+
+    spawn! { commands
+      (A(1), ChildOf(root));
+      (A(2), ChildOf(root)).[
+        (A(3));
+      ];
+    }
+  }
+
+  let mut world = run(system);
+  let root = world.query::<(Entity, &A)>().iter(&world).find(|(_, a)| a.0 == 0).unwrap().0;
+  assert_eq!(world.get::<Children>(root).unwrap().len(), 2);
+}
+
+#[test]
+fn observers_using_this() {
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      // mutates the component of `this`
+      (A(1)).(move |_: Trigger<Ping>, mut query: Query<&mut A>| {
+        query.get_mut(this).unwrap().0 = 10;
+      });
+
+      // inserts on `this`
+      (A(2)).(move |_: Trigger<Ping>, mut commands: Commands| {
+        commands.entity(this).insert(Pinged);
+      });
+
+      // despawns `this`
+      (A(3)).(move |_: Trigger<Ping>, mut commands: Commands| {
+        commands.entity(this).despawn();
+      });
+    }
+  }
+
+  let mut world = run(system);
+  ping(&mut world, 1);
+  ping(&mut world, 2);
+  ping(&mut world, 3);
+
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [2, 10]);
+  assert!(pinged(&mut world, 2));
+}
+
+#[test]
+fn code_blocks_with_world() {
+  let mut world = World::new();
+
+  spawn! { [&mut world]
+    (A(1)).{
+      // `entity` is a `&mut EntityWorldMut`, passing it on leaves the original usable
+      let entity: &mut EntityWorldMut = entity;
+      entity.insert(Name::new("one"));
+    }.{
+      entity.insert(Pinged);
+    }.[
+      (A(2)).{ entity.insert(Name::new("two")); };
+    ];
+  }
+
+  assert_eq!(world.query::<&Name>().iter(&world).count(), 2);
+  assert!(pinged(&mut world, 1));
+}
+
+#[test]
+fn conditional_parenting() {
+  fn system(mut commands: Commands) {
+    for wide in [true, false] {
+      spawn! { commands
+        a (A(if wide { 1 } else { 3 }));
+        b (A(if wide { 2 } else { 4 }));
+
+        if wide {
+          a > (A(10));
+        } else {
+          b > (A(20));
+        }
+
+        if let Some(n) = wide.then_some(30) {
+          b > (A(n));
+        } else {
+          [a] > (A(40));
+        }
+      }
+    }
+  }
+
+  let mut world = run(system);
+  let children = |world: &mut World, value: i32| {
+    let parent = world.query::<(Entity, &A)>().iter(world).find(|(_, a)| a.0 == value).unwrap().0;
+    let mut values = world.get::<Children>(parent).map_or(vec![], |children| {
+      children.iter().map(|child| world.get::<A>(child).unwrap().0).collect()
+    });
+    values.sort();
+    values
+  };
+
+  assert_eq!(children(&mut world, 1), [10]);
+  assert_eq!(children(&mut world, 2), [30]);
+  assert_eq!(children(&mut world, 3), [40]);
+  assert_eq!(children(&mut world, 4), [20]);
+}
+
+#[test]
+fn function_item_and_generic_observers() {
+  fn on_ping(trigger: Trigger<Ping>, mut commands: Commands) {
+    commands.entity(trigger.target()).insert(Pinged);
+  }
+
+  fn set_value<E: Event>(value: i32) -> impl FnMut(Trigger<E>, Query<&mut A>) {
+    move |trigger, mut query| { query.get_mut(trigger.target()).unwrap().0 = value; }
+  }
+
+  fn with_observer<M>(mut commands: Commands, observer: impl bevy::ecs::system::IntoObserverSystem<Ping, (), M>) {
+    spawn! { commands (A(2)).(observer); }
+  }
+
+  fn system(commands: Commands) {
+    with_observer(commands, set_value::<Ping>(20));
+  }
+
+  fn free(mut commands: Commands) {
+    spawn! { commands (A(1)).(on_ping); }
+  }
+
+  let mut world = run(free);
+  world.run_system_once(system).unwrap();
+
+  ping(&mut world, 1);
+  ping(&mut world, 2);
+  assert!(pinged(&mut world, 1));
+  assert!(world.query::<&A>().iter(&world).any(|a| a.0 == 20));
+}
+
+#[test]
+fn extensions_in_source_order() {
+  #[derive(Component)]
+  struct Pressed;
+
+  #[derive(Resource, Default)]
+  struct Order(Vec<&'static str>);
+
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      (A(1))
+        .(|_: Trigger<OnInsert, Pressed>, mut order: ResMut<Order>| order.0.push("observed"))
+        // inserted after the observer exists, so the observer sees it
+        .insert(Pressed)
+        .{ entity.commands().queue(|world: &mut World| world.resource_mut::<Order>().0.push("block")); }
+        .[ (A(2)); ]
+        .{ entity.commands().queue(|world: &mut World| world.resource_mut::<Order>().0.push("after")); };
+    }
+  }
+
+  let mut world = World::new();
+  world.init_resource::<Order>();
+  world.run_system_once(system).unwrap();
+  assert_eq!(world.resource::<Order>().0, ["observed", "block", "after"]);
+}
+
+#[test]
+fn pre_assigned_by_named_if_and_inserted() {
+  fn system(mut commands: Commands) {
+    let base = commands.spawn(A(0)).id();
+    let mut picked = vec![];
+
+    spawn! { commands
+      (A(1)).[
+        pre icon, extended;
+        icon if false { (A(2)) } else { (A(3)) };
+        extended = base + (Pinged);
+        { picked.push(icon); picked.push(extended); };
+
+        for i in 4..6 {
+          pre item;
+          item if i % 2 == 0 { (A(i)) } else { (A(-i)) };
+          { picked.push(item); };
+        }
+      ];
+    }
+
+    for (i, entity) in picked.into_iter().enumerate() {
+      commands.entity(entity).insert(Index(i));
+    }
+  }
+
+  #[derive(Component)]
+  struct Index(usize);
+
+  let mut world = run(system);
+  let mut picked = world.query::<(&A, &Index)>().iter(&world).map(|(a, i)| (i.0, a.0)).collect::<Vec<_>>();
+  picked.sort();
+  assert_eq!(picked, [(0, 3), (1, 0), (2, 4), (3, -5)]);
+}
+
+#[test]
+fn children_at_index() {
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      list (A(0)).[
+        (A(2));
+        (A(5));
+      ];
+
+      list + ().children_at(1)[
+        (A(3));
+        (A(4));
+      ];
+
+      // only the flow statements are still appended
+      list + ().children_at(0)[
+        (A(1));
+        for i in 6..8 { (A(i)); }
+      ];
+    }
+  }
+
+  let mut world = run(system);
+  assert_eq!(children_of(&mut world, 0), [1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn replace_respawns_the_entity() {
+  fn system(mut commands: Commands) {
+    let panel = commands.spawn(A(1)).with_child(A(2)).id();
+
+    spawn! { commands
+      replace panel (A(3)).[
+        (A(4));
+      ];
+
+      (A(5), ChildOf(panel));
+    }
+  }
+
+  let mut world = run(system);
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [3, 4, 5]);
+  assert_eq!(children_of(&mut world, 3), [4, 5]);
+}
+
+#[test]
+fn named_if_binds_the_taken_branch() {
+  fn system(mut commands: Commands) {
+    let dark = false;
+
+    spawn! { commands
+      icon if dark { (A(1)) } else { (A(2)) };
+      fallback if let Some(v) = None::<i32> { (A(v)) } else if dark { (A(3)) } else { (A(4)) };
+
+      root (A(0));
+      root > fallback;
+      root > icon;
+    }
+  }
+
+  let mut world = run(system);
+  assert_eq!(world.query::<&A>().iter(&world).count(), 3);
+  assert_eq!(children_of(&mut world, 0), [4, 2]);
+}
+
+#[test]
+fn loop_until_break() {
+  fn system(mut commands: Commands) {
+    let mut n = 0;
+
+    spawn! { commands
+      (A(0)).[
+        loop {
+          { n += 1; };
+
+          if n > 3 {
+            break;
+          }
+
+          (A(n));
+        }
+      ];
+    }
+  }
+
+  let mut world = run(system);
+  assert_eq!(children_of(&mut world, 0), [1, 2, 3]);
+}
+
+#[test]
+fn match_arms_and_guards() {
+  enum Reward { Coins(i32), Item, Nothing }
+
+  fn system(mut commands: Commands) {
+    spawn! { commands
+      (A(0)).[
+        for reward in [Reward::Coins(200), Reward::Coins(5), Reward::Nothing, Reward::Item] {
+          match reward {
+            Reward::Coins(n) if n > 100 => {
+              (A(-n));
+            }
+            Reward::Coins(n) => {
+              (A(n));
+            }
+            Reward::Item => {
+              (A(1));
+              (A(2));
+            }
+            Reward::Nothing => {}
+          }
+        }
+      ];
+    }
+  }
+
+  let mut world = run(system);
+  assert_eq!(children_of(&mut world, 0), [-200, 5, 1, 2]);
+}
+
+#[test]
+fn labeled_break_and_continue() {
+  fn system(mut commands: Commands) {
+    let rows = [[1, 2, 3], [4, -1, 6], [7, 8, 9]];
+
+    spawn! { commands
+      (A(0)).[
+        'rows: for row in rows {
+          for cell in row {
+            if cell == 2 {
+              continue 'rows;
+            }
+
+            if cell < 0 {
+              break 'rows;
+            }
+
+            (A(cell));
+          }
+        }
+
+        'outer: loop {
+          for i in 10.. {
+            if i == 12 {
+              break 'outer;
+            }
+
+            (A(i));
+          }
+        }
+      ];
+    }
+  }
+
+  let mut world = run(system);
+  assert_eq!(children_of(&mut world, 0), [1, 4, 10, 11]);
+}
+
+#[test]
+fn commands_rebound_in_code_blocks() {
+  fn system(mut commands: Commands) {
+    spawn! { [&mut commands]
+      (A(0))
+        .{
+          commands.spawn(A(1));
+          commands.entity(this).with_child(A(2));
+        }
+        .{ entity.insert(Pinged); };
+    }
+
+    // the spawner only borrowed it, and the code block took its own
+    spawn! { [commands reborrow]
+      (A(3)).{ commands.spawn(A(4)); };
+    }
+
+    commands.spawn(A(5));
+  }
+
+  let mut world = run(system);
+  let mut values = world.query::<&A>().iter(&world).map(|a| a.0).collect::<Vec<_>>();
+  values.sort();
+  assert_eq!(values, [0, 1, 2, 3, 4, 5]);
+  assert_eq!(children_of(&mut world, 0), [2]);
+  assert!(pinged(&mut world, 0));
+}
+
+#[test]
+fn batched_children() {
+  fn system(mut commands: Commands) {
+    let existing = commands.spawn(A(0)).with_child(A(1)).id();
+    let empty    = commands.spawn(A(10)).id();
+
+    spawn! { commands
+      (A(20)).[
+        for! i in 21..24 {
+          (A(i));
+        }
+      ];
+
+      for! i in 2..5 {
+        existing > (A(i));
+      }
+
+      for! i in 11..13 {
+        empty > (A(i));
+      }
+    }
+  }
+
+  let mut world = run(system);
+  assert_eq!(children_of(&mut world, 20), [21, 22, 23]);
+  assert_eq!(children_of(&mut world, 0), [1, 2, 3, 4]);
+  assert_eq!(children_of(&mut world, 10), [11, 12]);
+}
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+use bevy_toolbox::prelude::*;
+
+fn setup(mut commands: Commands) {
+  let hidden = true;
+
+  spawn! { commands
+    (Node::default()).[
+      if hidden {
+        break;
+      }
+
+      (Text::new("shown"));
+    ];
+  }
+}
+
+fn main() {
+  App::new().add_systems(Startup, setup).run();
+}
@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use bevy_toolbox::prelude::*;
+
+fn setup(mut commands: Commands) {
+  spawn! { commands
+    (Node::default())
+      .[ (Text::new("first")); ]
+      .observe(|_: Trigger<Pointer<Click>>| {})
+      .[ (Text::new("second")); ];
+  }
+}
+
+fn main() {
+  App::new().add_systems(Startup, setup).run();
+}
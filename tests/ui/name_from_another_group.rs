@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+use bevy_toolbox::prelude::*;
+
+fn setup(mut commands: Commands) {
+  spawn! { commands
+    (Node::default()).[ icon (Text::new("icon")); ];
+    (Node::default()).{ entity.add_child(icon); };
+  }
+}
+
+fn main() {
+  App::new().add_systems(Startup, setup).run();
+}
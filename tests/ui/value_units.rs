@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+use bevy_toolbox::prelude::*;
+
+fn main() {
+  let x = 10.0;
+  let _: Val = v!({x});
+  let _: Val = v!({x}rem);
+  let _: Val = v!(10rem);
+}